@@ -2,4 +2,4 @@
 cd vp_proxy
 cargo run --features export-api > candid.did
 cd ..
-cargo build --release --target wasm32-unknown-unknown --features export-api
\ No newline at end of file
+cargo build --release --target wasm32-unknown-unknown --features export-api -p vp_proxy
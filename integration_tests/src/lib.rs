@@ -0,0 +1,292 @@
+//! PocketIC harness that runs the vp_proxy wasm against the mock SNS governance and ledger canisters.
+//! The wasm files are built by `test.sh`; their paths can be overridden with the
+//! `VP_PROXY_WASM`, `MOCK_GOVERNANCE_WASM` and `MOCK_LEDGER_WASM` environment variables.
+
+pub mod types;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use candid::{decode_args, decode_one, encode_args, utils::ArgumentEncoder, CandidType, Nat};
+use ic_sns_governance::pb::v1::{NeuronId, ProposalId};
+use pocket_ic::{PocketIc, WasmResult};
+use serde::de::DeserializeOwned;
+
+pub use candid::Principal;
+
+use crate::types::{CanisterError, MockProposal, ProxyProposalQuery, RegisteredVote};
+
+const WASM_DIR: &str = "../target/wasm32-unknown-unknown/release";
+
+/// Voting period used for scripted proposals, matching the SNS default of four days.
+pub const VOTING_PERIOD_SECONDS: u64 = 4 * 86_400;
+
+/// Rounds executed after every time jump, enough for timers and their inter-canister calls to settle.
+const SETTLE_ROUNDS: usize = 20;
+
+fn load_wasm(env_var: &str, file_name: &str) -> Vec<u8> {
+    let path = std::env::var(env_var)
+        .unwrap_or_else(|_| format!("{}/{}/{}", env!("CARGO_MANIFEST_DIR"), WASM_DIR, file_name));
+    std::fs::read(&path).unwrap_or_else(|_| {
+        panic!(
+            "Could not read {}. Build the wasm files with test.sh.",
+            path
+        )
+    })
+}
+
+pub struct TestEnv {
+    pub pic: PocketIc,
+    pub controller: Principal,
+    pub proxy: Principal,
+    pub governance: Principal,
+    pub ledger: Principal,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let pic = PocketIc::new();
+        let controller = Principal::from_slice(&[1; 29]);
+
+        let install = |wasm: Vec<u8>| {
+            let canister = pic.create_canister_with_settings(Some(controller), None);
+            pic.add_cycles(canister, 2_000_000_000_000);
+            pic.install_canister(canister, wasm, encode_args(()).unwrap(), Some(controller));
+            canister
+        };
+
+        let proxy = install(load_wasm("VP_PROXY_WASM", "vp_proxy.wasm"));
+        let governance = install(load_wasm(
+            "MOCK_GOVERNANCE_WASM",
+            "mock_sns_governance.wasm",
+        ));
+        let ledger = install(load_wasm("MOCK_LEDGER_WASM", "mock_icrc1_ledger.wasm"));
+
+        Self {
+            pic,
+            controller,
+            proxy,
+            governance,
+            ledger,
+        }
+    }
+
+    /// Points the proxy to the mock canisters and stakes a neuron for it.
+    pub fn with_neuron() -> Self {
+        let env = Self::new();
+        env.configure();
+        env.create_neuron(Nat::from(100_000_000_u64), 1)
+            .expect("Neuron creation failed");
+        env
+    }
+
+    pub fn update<T: CandidType + DeserializeOwned>(
+        &self,
+        canister: Principal,
+        sender: Principal,
+        method: &str,
+        args: impl ArgumentEncoder,
+    ) -> T {
+        let result = self
+            .pic
+            .update_call(canister, sender, method, encode_args(args).unwrap())
+            .unwrap_or_else(|err| panic!("{} failed: {:?}", method, err));
+        decode_one(&reply(method, result)).unwrap()
+    }
+
+    pub fn update_unit(
+        &self,
+        canister: Principal,
+        sender: Principal,
+        method: &str,
+        args: impl ArgumentEncoder,
+    ) {
+        let result = self
+            .pic
+            .update_call(canister, sender, method, encode_args(args).unwrap())
+            .unwrap_or_else(|err| panic!("{} failed: {:?}", method, err));
+        decode_args::<()>(&reply(method, result)).unwrap()
+    }
+
+    pub fn query<T: CandidType + DeserializeOwned>(
+        &self,
+        canister: Principal,
+        method: &str,
+        args: impl ArgumentEncoder,
+    ) -> T {
+        let result = self
+            .pic
+            .query_call(
+                canister,
+                Principal::anonymous(),
+                method,
+                encode_args(args).unwrap(),
+            )
+            .unwrap_or_else(|err| panic!("{} failed: {:?}", method, err));
+        decode_one(&reply(method, result)).unwrap()
+    }
+
+    /// Calls an update method of the proxy as its controller.
+    pub fn proxy_update<T: CandidType + DeserializeOwned>(
+        &self,
+        method: &str,
+        args: impl ArgumentEncoder,
+    ) -> T {
+        self.update(self.proxy, self.controller, method, args)
+    }
+
+    pub fn configure(&self) {
+        let governance: Result<(), CanisterError> =
+            self.proxy_update("set_governance_id", (self.governance,));
+        governance.unwrap();
+        let ledger: Result<(), CanisterError> = self.proxy_update("set_ledger_id", (self.ledger,));
+        ledger.unwrap();
+    }
+
+    pub fn create_neuron(&self, amount: Nat, nonce: u64) -> Result<NeuronId, CanisterError> {
+        self.proxy_update("create_neuron", (amount, nonce))
+    }
+
+    /// Registers the neuron in the mock governance canister, then adds it to the council.
+    pub fn add_council_member(&self, name: &str, neuron_id: &str) {
+        self.add_neuron(neuron_id);
+        let result: Result<(), CanisterError> = self.proxy_update(
+            "add_council_member",
+            (name.to_string(), neuron_id.to_string()),
+        );
+        result.unwrap();
+    }
+
+    pub fn add_neuron(&self, neuron_id: &str) {
+        self.update_unit(
+            self.governance,
+            self.controller,
+            "add_neuron",
+            (neuron_id.to_string(),),
+        );
+    }
+
+    /// Scripts proposal 1, created a day ago, as the starting point for `watch_proposals`.
+    pub fn add_baseline_proposal(&self) -> MockProposal {
+        let baseline = MockProposal {
+            creation_timestamp_seconds: Some(self.now_seconds() - 86_400),
+            ..proposal(1, 0, "Baseline proposal")
+        };
+        self.add_proposal(&baseline);
+        baseline
+    }
+
+    /// Starts watching proposals newer than `from`, which must already be scripted.
+    pub fn watch_proposals(&self, from: &MockProposal) -> Result<(), CanisterError> {
+        let result = self.proxy_update(
+            "watch_proposals",
+            (
+                ProposalId { id: from.id },
+                from.action,
+                from.creation_timestamp_seconds.unwrap(),
+            ),
+        );
+        self.settle();
+        result
+    }
+
+    pub fn add_proposal(&self, proposal: &MockProposal) {
+        self.update_unit(
+            self.governance,
+            self.controller,
+            "add_proposal",
+            (proposal.clone(),),
+        );
+    }
+
+    pub fn set_ballot(&self, proposal_id: u64, neuron_id: &str, vote: i32) {
+        self.update_unit(
+            self.governance,
+            self.controller,
+            "set_ballot",
+            (proposal_id, neuron_id.to_string(), vote),
+        );
+    }
+
+    pub fn close_proposal(&self, proposal_id: u64) {
+        self.update_unit(
+            self.governance,
+            self.controller,
+            "close_proposal",
+            (proposal_id,),
+        );
+    }
+
+    pub fn registered_votes(&self) -> Vec<RegisteredVote> {
+        self.query(self.governance, "get_registered_votes", ())
+    }
+
+    pub fn proposal_history(&self) -> Vec<ProxyProposalQuery> {
+        self.query(self.proxy, "get_proposal_history", ())
+    }
+
+    pub fn proposal_watchlist(&self) -> Vec<ProxyProposalQuery> {
+        self.query(self.proxy, "get_proposal_watchlist", ())
+    }
+
+    pub fn neuron_id(&self) -> Result<NeuronId, CanisterError> {
+        self.query(self.proxy, "get_neuron_id", ())
+    }
+
+    pub fn now_seconds(&self) -> u64 {
+        self.pic
+            .get_time()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    /// Moves the clock forward and lets the triggered timers finish their work.
+    pub fn advance_time(&self, duration: Duration) {
+        self.pic.advance_time(duration);
+        self.settle();
+    }
+
+    pub fn settle(&self) {
+        for _ in 0..SETTLE_ROUNDS {
+            self.pic.tick();
+        }
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A proposal with the default voting period, created at the current time.
+pub fn proposal(id: u64, action: u64, title: &str) -> MockProposal {
+    MockProposal {
+        id,
+        action,
+        title: title.to_string(),
+        initial_voting_period_seconds: VOTING_PERIOD_SECONDS,
+        ..Default::default()
+    }
+}
+
+/// A valid 32 byte neuron ID, hex encoded, made of the repeated `byte`.
+pub fn neuron(byte: u8) -> String {
+    format!("{:02x}", byte).repeat(32)
+}
+
+/// Encodes a neuron id the same way SNS governance keys its ballots.
+pub fn neuron_hex(neuron_id: &NeuronId) -> String {
+    neuron_id
+        .id
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn reply(method: &str, result: WasmResult) -> Vec<u8> {
+    match result {
+        WasmResult::Reply(bytes) => bytes,
+        WasmResult::Reject(message) => panic!("{} was rejected: {}", method, message),
+    }
+}
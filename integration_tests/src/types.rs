@@ -0,0 +1,444 @@
+//! Mirrors of the candid types exposed by vp_proxy and the mock canisters.
+//! Records only need the fields the tests read; variants must list every case the canisters can return.
+
+use candid::{CandidType, Nat, Principal};
+use ic_sns_governance::pb::v1::ProposalId;
+use serde::Deserialize;
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum CanisterError {
+    Unknown(String),
+    Unauthorized,
+    ConfigurationError,
+    NeuronAlreadySet,
+    WatchingIsAlreadyInProgress,
+    WatchingIsAlreadyStopped,
+    ProposalIsNotInWatchlist(u64),
+    ProposalLocked(u64),
+    FilterRuleNotFound(u64),
+    VotingPolicyNotFound(String),
+    VotingPolicyInUse(String),
+    InvalidVotingPolicy(String),
+    UnknownActionType(u64),
+    ActionTypeAlreadyListed(u64),
+    InvalidNeuronId(String),
+    InvalidCouncilMemberName(String),
+    NeuronNotFound(String),
+    DuplicateCouncilMember(String),
+    CouncilMemberNotFound(String),
+    InvalidCouncilTerm(String),
+    InvalidCouncilMemberPrincipal(String),
+    RoleAssignmentNotFound,
+    ApprovalRequired,
+    InvalidMultisigPolicy(String),
+    PendingActionNotFound(u64),
+    AlreadyApproved(u64),
+    TimelockRequired,
+    QueuedChangeNotFound(u64),
+    InvalidEarlyDecisionInterval(u64),
+    InvalidInactivityRule(String),
+    MemberNotSuspended(String),
+    InvalidDelegation(String),
+    DelegationNotFound(String),
+    InvalidVetoRight(String),
+    VetoRightNotFound(String),
+    InvalidVotingMode(String),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum ParticipationStatus {
+    Undecided,
+    TooLateToParticipate,
+    VotedFor,
+    VotedAgainst,
+    FailedToVote,
+    Abstained,
+    Skipped { reason: SkipReason },
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    ExcludedAction,
+    FilterRule { rule_id: u64 },
+    AlreadyClosed,
+    DeadlinePassed,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct ProxyProposalQuery {
+    pub id: ProposalId,
+    pub action: u64,
+    pub creation_timestamp: u64,
+    pub participation_status: ParticipationStatus,
+    pub timer_scheduled_for: Option<u64>,
+    pub policy: Option<String>,
+    pub council_epoch: Option<u64>,
+    pub evaluation: Option<EvaluationRecord>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct EvaluationRecord {
+    pub eligible_members: u64,
+    pub voted: u64,
+    pub yes: u64,
+    pub ineligible_members: Vec<CouncilMember>,
+    pub no: u64,
+    pub not_voted: u64,
+    pub member_ballots: Vec<MemberBallot>,
+    pub suspended_members: Vec<CouncilMember>,
+    pub vetoed_by: Vec<CouncilMember>,
+    pub mirrored_neuron: Option<String>,
+    pub mirrored_neurons: Vec<String>,
+    pub quorum_percent: Option<u8>,
+    pub approval_percent: Option<u8>,
+    pub evaluated_at_seconds: u64,
+    pub vote_sent: Option<BallotChoice>,
+    pub governance_response: Option<VoteResponse>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BallotChoice {
+    Yes,
+    No,
+    NotVoted,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct MemberBallot {
+    pub name: String,
+    pub neuron_id: String,
+    pub vote: BallotChoice,
+    pub delegate: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct Delegation {
+    pub delegator: String,
+    pub delegate: String,
+    pub starts_at: u64,
+    pub ends_at: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum VoteResponse {
+    Registered,
+    Failed(String),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct LiveTally {
+    pub proposal_id: u64,
+    pub council_epoch: u64,
+    pub policy: String,
+    pub tally: EvaluationRecord,
+    pub projected_vote: BallotChoice,
+    pub pending_members: Vec<CouncilMember>,
+    pub seconds_until_evaluation: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct CouncilMember {
+    pub name: String,
+    pub neuron_id: String,
+    pub additional_neuron_ids: Option<Vec<String>>,
+    pub term_start: Option<u64>,
+    pub term_end: Option<u64>,
+    pub principal: Option<Principal>,
+}
+
+impl CouncilMember {
+    /// A member with a single neuron.
+    pub fn new(name: &str, neuron_id: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            neuron_id: neuron_id.to_string(),
+            additional_neuron_ids: None,
+            term_start: None,
+            term_end: None,
+            principal: None,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum NeuronCombineRule {
+    FirstVote,
+    MajorityOfNeurons,
+    AnyYes,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum VotingMode {
+    Council,
+    Mirror(Vec<String>),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct VetoRight {
+    pub neuron_id: String,
+    pub action_types: Vec<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct InactivityRule {
+    pub missed_proposals: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct MemberActivity {
+    pub name: String,
+    pub neuron_id: String,
+    pub missed_in_a_row: u64,
+    pub suspended_since: Option<u64>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct SuspensionEvent {
+    pub name: String,
+    pub change: SuspensionChange,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum SuspensionChange {
+    Suspended { missed_proposals: u64 },
+    ReinstatedByVote,
+    ReinstatedBy(Principal),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct CouncilDiff {
+    pub epoch: u64,
+    pub added: Vec<CouncilMember>,
+    pub removed: Vec<CouncilMember>,
+    pub updated: Vec<CouncilMember>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum CouncilSource {
+    Snapshot,
+    Live,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct CouncilEpoch {
+    pub epoch: u64,
+    pub members: Vec<CouncilMember>,
+    pub changes: CouncilDiff,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Admin,
+    Operator,
+    Auditor,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoleAssignment {
+    pub principal: Principal,
+    pub role: Role,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct MultisigPolicy {
+    pub signers: Vec<Principal>,
+    pub threshold: u8,
+    pub expiry_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum SensitiveAction {
+    EmergencyReset,
+    SetGovernanceId(Principal),
+    StopTimers,
+    CreateNeuron { amount: Nat, nonce: u64 },
+    IncreaseDissolveDelay(u32),
+    SetMultisigPolicy(Option<MultisigPolicy>),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct PendingAction {
+    pub id: u64,
+    pub action: SensitiveAction,
+    pub approvals: Vec<Principal>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum ApprovalOutcome {
+    Pending { id: u64, approvals: u64 },
+    Executed { id: u64 },
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    AddCouncilMember(CouncilMember),
+    UpdateCouncilMember {
+        neuron_id: String,
+        member: CouncilMember,
+    },
+    RemoveCouncilMember(String),
+    SetCouncil(Vec<CouncilMember>),
+    AllowActionType(u64),
+    DisallowActionType(u64),
+    SetExclusionMode(ExclusionMode),
+    SetVotingPolicy(VotingPolicy),
+    RemoveVotingPolicy(String),
+    SetTimelockDelay(u64),
+    SetVotingMode(VotingMode),
+    SetCouncilSource(CouncilSource),
+    SetNeuronCombineRule(NeuronCombineRule),
+    SetDelegation(Delegation),
+    RemoveDelegation(String),
+    SetVetoRight(VetoRight),
+    RemoveVetoRight(String),
+    SetInactivityRule(Option<InactivityRule>),
+    ReinstateCouncilMember(String),
+    AddFilterRule {
+        matcher: FilterMatcher,
+        action: FilterAction,
+    },
+    RemoveFilterRule(u64),
+    SetFlaggedActionHandling(FlaggedActionHandling),
+    AcknowledgeActionType(u64),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct QueuedChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub queued_at_seconds: u64,
+    pub activates_at_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct DecisionRecord {
+    pub proposal_id: u64,
+    pub vote: ParticipationStatus,
+    pub decided_at_seconds: u64,
+    pub policy: Option<VotingPolicy>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct DecisionEntry {
+    pub index: u64,
+    pub record: DecisionRecord,
+    pub previous_hash: Vec<u8>,
+    pub hash: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct DecisionLogPage {
+    pub entries: Vec<DecisionEntry>,
+    pub total: u64,
+    pub tip_hash: Vec<u8>,
+    pub certificate: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct MemberStats {
+    pub name: String,
+    pub neuron_id: String,
+    pub eligible: u64,
+    pub voted: u64,
+    pub yes: u64,
+    pub no: u64,
+    pub matched_proxy_vote: u64,
+    pub delegated: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct AuditEntry {
+    pub id: u64,
+    pub caller: Principal,
+    pub endpoint: String,
+    pub arguments: String,
+    pub result: String,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditEntry>,
+    pub total: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum FilterMatcher {
+    TitlePrefix(String),
+    TitleContains(String),
+    SummaryKeyword(String),
+    UrlDomain(String),
+    ProposerNeuron(String),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub enum FilterAction {
+    Skip,
+    ForceAbstain,
+    ApplyPolicy(String),
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct FilterRule {
+    pub id: u64,
+    pub matcher: FilterMatcher,
+    pub action: FilterAction,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone)]
+pub struct FilterSample {
+    pub title: String,
+    pub summary: String,
+    pub url: String,
+    pub proposer: Option<String>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct VotingPolicy {
+    pub name: String,
+    pub quorum_percent: u8,
+    pub approval_percent: u8,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Default)]
+pub struct MockProposal {
+    pub id: u64,
+    pub action: u64,
+    pub title: String,
+    pub summary: String,
+    pub url: String,
+    pub creation_timestamp_seconds: Option<u64>,
+    pub initial_voting_period_seconds: u64,
+    pub proposer: Option<String>,
+    pub ballots: Vec<(String, i32)>,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct RegisteredVote {
+    pub proposal_id: u64,
+    pub neuron_id: String,
+    pub vote: i32,
+    pub timestamp_seconds: u64,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ExclusionMode {
+    Denylist,
+    Allowlist,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum FlaggedActionHandling {
+    FollowExclusionMode,
+    Ignore,
+}
+
+#[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
+pub struct ActionType {
+    pub id: u64,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub is_generic: bool,
+    pub flagged: bool,
+}
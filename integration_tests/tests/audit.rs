@@ -0,0 +1,56 @@
+use integration_tests::{
+    types::{AuditLogPage, CanisterError, Role},
+    Principal, TestEnv,
+};
+
+fn audit_log(env: &TestEnv, sender: Principal, offset: u64, limit: u64) -> AuditLogPage {
+    let page: Result<AuditLogPage, CanisterError> =
+        env.update(env.proxy, sender, "get_audit_log", (offset, limit));
+    page.unwrap()
+}
+
+#[test]
+fn mutating_calls_are_recorded() {
+    let env = TestEnv::new();
+    let stranger = Principal::from_slice(&[41; 29]);
+    let auditor = Principal::from_slice(&[42; 29]);
+
+    env.configure();
+    let result: Result<(), CanisterError> =
+        env.update(env.proxy, stranger, "set_governance_id", (stranger,));
+    assert_eq!(result, Err(CanisterError::Unauthorized));
+    let result: Result<(), CanisterError> = env.proxy_update("remove_filter_rule", (7_u64,));
+    assert_eq!(result, Err(CanisterError::FilterRuleNotFound(7)));
+    let result: Result<(), CanisterError> =
+        env.proxy_update("grant_role", (auditor, Role::Auditor));
+    result.unwrap();
+
+    let page = audit_log(&env, auditor, 0, 10);
+    assert_eq!(page.total, 4);
+    let endpoints: Vec<&str> = page
+        .entries
+        .iter()
+        .map(|entry| entry.endpoint.as_str())
+        .collect();
+    assert_eq!(
+        endpoints,
+        vec![
+            "set_governance_id",
+            "set_ledger_id",
+            "remove_filter_rule",
+            "grant_role"
+        ]
+    );
+    let failed = &page.entries[2];
+    assert_eq!(failed.caller, env.controller);
+    assert_eq!(failed.arguments, "7");
+    assert_eq!(failed.result, "Err(FilterRuleNotFound(7))");
+
+    let page = audit_log(&env, auditor, 3, 10);
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.entries[0].id, 3);
+
+    let result: Result<AuditLogPage, CanisterError> =
+        env.update(env.proxy, stranger, "get_audit_log", (0_u64, 10_u64));
+    assert_eq!(result.err(), Some(CanisterError::Unauthorized));
+}
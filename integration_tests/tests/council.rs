@@ -0,0 +1,370 @@
+use std::time::Duration;
+
+use integration_tests::{
+    neuron,
+    types::{CanisterError, CouncilDiff, CouncilEpoch, CouncilMember, Delegation, VetoRight},
+    Principal, TestEnv, VOTING_PERIOD_SECONDS,
+};
+
+fn add_member(env: &TestEnv, name: &str, neuron_id: &str) -> Result<(), CanisterError> {
+    env.proxy_update(
+        "add_council_member",
+        (name.to_string(), neuron_id.to_string()),
+    )
+}
+
+fn council(env: &TestEnv) -> Vec<CouncilMember> {
+    env.query(env.proxy, "get_council", ())
+}
+
+#[test]
+fn add_council_member_validates_the_neuron_id() {
+    let env = TestEnv::new();
+    env.configure();
+
+    assert_eq!(
+        add_member(&env, "alice", "aa01"),
+        Err(CanisterError::InvalidNeuronId("aa01".to_string()))
+    );
+    let not_hex = "zz".repeat(32);
+    assert_eq!(
+        add_member(&env, "alice", &not_hex),
+        Err(CanisterError::InvalidNeuronId(not_hex))
+    );
+    assert_eq!(
+        add_member(&env, "alice", &neuron(1)),
+        Err(CanisterError::NeuronNotFound(neuron(1)))
+    );
+    assert_eq!(
+        add_member(&env, " ", &neuron(1)),
+        Err(CanisterError::InvalidCouncilMemberName(" ".to_string()))
+    );
+    assert!(council(&env).is_empty());
+}
+
+#[test]
+fn add_council_member_normalizes_and_rejects_duplicates() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_neuron(&neuron(0xab));
+    env.add_neuron(&neuron(2));
+
+    add_member(&env, " alice ", &neuron(0xab).to_uppercase()).unwrap();
+    assert_eq!(
+        council(&env),
+        vec![CouncilMember::new("alice", &neuron(0xab))]
+    );
+
+    assert_eq!(
+        add_member(&env, "bob", &neuron(0xab)),
+        Err(CanisterError::DuplicateCouncilMember("alice".to_string()))
+    );
+    assert_eq!(
+        add_member(&env, "Alice", &neuron(2)),
+        Err(CanisterError::DuplicateCouncilMember("alice".to_string()))
+    );
+    assert_eq!(council(&env).len(), 1);
+}
+
+#[test]
+fn update_and_remove_council_members() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    env.add_council_member("bob", &neuron(2));
+    env.add_neuron(&neuron(3));
+
+    let renamed = CouncilMember::new("alice b.", &neuron(3));
+    let result: Result<(), CanisterError> =
+        env.proxy_update("update_council_member", (neuron(1), renamed.clone()));
+    result.unwrap();
+
+    let clash = CouncilMember::new("bob", &neuron(3));
+    let result: Result<(), CanisterError> =
+        env.proxy_update("update_council_member", (neuron(3), clash));
+    assert_eq!(
+        result,
+        Err(CanisterError::DuplicateCouncilMember("bob".to_string()))
+    );
+
+    let result: Result<(), CanisterError> = env.proxy_update("remove_council_member", (neuron(1),));
+    assert_eq!(result, Err(CanisterError::CouncilMemberNotFound(neuron(1))));
+    let result: Result<(), CanisterError> = env.proxy_update("remove_council_member", (neuron(2),));
+    result.unwrap();
+
+    assert_eq!(council(&env), vec![renamed]);
+}
+
+#[test]
+fn set_council_replaces_the_council_and_records_an_epoch() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    env.add_council_member("bob", &neuron(2));
+    env.add_neuron(&neuron(3));
+
+    let next = vec![
+        CouncilMember::new("alice b.", &neuron(1)),
+        CouncilMember::new("carol", &neuron(3)),
+    ];
+    let diff: Result<CouncilDiff, CanisterError> = env.proxy_update("set_council", (next.clone(),));
+    let diff = diff.unwrap();
+    assert_eq!(
+        diff,
+        CouncilDiff {
+            epoch: 3,
+            added: vec![CouncilMember::new("carol", &neuron(3))],
+            removed: vec![CouncilMember::new("bob", &neuron(2))],
+            updated: vec![CouncilMember::new("alice b.", &neuron(1))],
+        }
+    );
+    assert_eq!(council(&env), next);
+
+    let epochs: Vec<CouncilEpoch> = env.query(env.proxy, "get_council_epochs", ());
+    let numbers: Vec<u64> = epochs.iter().map(|epoch| epoch.epoch).collect();
+    assert_eq!(numbers, vec![1, 2, 3]);
+    assert_eq!(epochs[2].members, next);
+    assert_eq!(epochs[2].changes, diff);
+
+    // an unchanged council keeps its epoch
+    let diff: Result<CouncilDiff, CanisterError> = env.proxy_update("set_council", (next,));
+    assert_eq!(diff.unwrap().epoch, 3);
+}
+
+#[test]
+fn set_council_rejects_invalid_input_as_a_unit() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    env.add_neuron(&neuron(2));
+
+    let unknown_neuron = vec![
+        CouncilMember::new("bob", &neuron(2)),
+        CouncilMember::new("carol", &neuron(3)),
+    ];
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (unknown_neuron,));
+    assert_eq!(result, Err(CanisterError::NeuronNotFound(neuron(3))));
+
+    let duplicate_name = vec![
+        CouncilMember::new("bob", &neuron(1)),
+        CouncilMember::new("Bob", &neuron(2)),
+    ];
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (duplicate_name,));
+    assert_eq!(
+        result,
+        Err(CanisterError::DuplicateCouncilMember("bob".to_string()))
+    );
+
+    assert_eq!(council(&env), vec![CouncilMember::new("alice", &neuron(1))]);
+    let epochs: Vec<CouncilEpoch> = env.query(env.proxy, "get_council_epochs", ());
+    assert_eq!(epochs.len(), 1);
+}
+
+#[test]
+fn members_can_vote_with_several_neurons() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    env.add_neuron(&neuron(2));
+    env.add_neuron(&neuron(3));
+
+    let mut alice = CouncilMember::new("alice", &neuron(1));
+    alice.additional_neuron_ids = Some(vec![neuron(2).to_uppercase()]);
+    let mut bob = CouncilMember::new("bob", &neuron(3));
+    bob.additional_neuron_ids = Some(vec![neuron(2)]);
+
+    // neurons cannot be shared between members
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (vec![alice.clone(), bob.clone()],));
+    assert_eq!(
+        result,
+        Err(CanisterError::DuplicateCouncilMember("alice".to_string()))
+    );
+
+    bob.additional_neuron_ids = Some(vec![neuron(4)]);
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (vec![alice.clone(), bob.clone()],));
+    assert_eq!(result, Err(CanisterError::NeuronNotFound(neuron(4))));
+
+    bob.additional_neuron_ids = None;
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (vec![alice.clone(), bob.clone()],));
+    let diff = result.unwrap();
+    alice.additional_neuron_ids = Some(vec![neuron(2)]);
+    assert_eq!(diff.updated, vec![alice.clone()]);
+    assert_eq!(diff.added, vec![bob.clone()]);
+    assert_eq!(council(&env), vec![alice, bob]);
+}
+
+#[test]
+fn members_join_and_leave_with_their_term() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_neuron(&neuron(1));
+    env.add_neuron(&neuron(2));
+    let now = env.now_seconds();
+
+    let mut alice = CouncilMember::new("alice", &neuron(1));
+    alice.term_end = Some(now + 7200);
+    let mut bob = CouncilMember::new("bob", &neuron(2));
+    bob.term_start = Some(now + 3600);
+
+    let mut invalid = bob.clone();
+    invalid.term_end = Some(now + 1800);
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (vec![alice.clone(), invalid],));
+    assert_eq!(
+        result,
+        Err(CanisterError::InvalidCouncilTerm("bob".to_string()))
+    );
+
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (vec![alice.clone(), bob.clone()],));
+    assert_eq!(result.unwrap().added, vec![alice.clone()]);
+    assert_eq!(council(&env), vec![alice.clone()]);
+    let upcoming: Vec<CouncilMember> = env.query(env.proxy, "get_upcoming_council_members", ());
+    assert_eq!(upcoming, vec![bob.clone()]);
+    let expirations: Vec<CouncilMember> = env.query(env.proxy, "get_upcoming_expirations", ());
+    assert_eq!(expirations, vec![alice.clone()]);
+
+    env.advance_time(Duration::from_secs(3600));
+    assert_eq!(council(&env), vec![alice, bob.clone()]);
+
+    env.advance_time(Duration::from_secs(3600));
+    assert_eq!(council(&env), vec![bob]);
+    let epochs: Vec<CouncilEpoch> = env.query(env.proxy, "get_council_epochs", ());
+    assert_eq!(epochs.len(), 3);
+}
+
+#[test]
+fn bound_members_manage_their_own_record() {
+    let env = TestEnv::new();
+    env.configure();
+    for byte in 1..=4 {
+        env.add_neuron(&neuron(byte));
+    }
+    let alice_principal = Principal::from_slice(&[11; 29]);
+    let mut alice = CouncilMember::new("alice", &neuron(1));
+    alice.principal = Some(alice_principal);
+    let bob = CouncilMember::new("bob", &neuron(2));
+    let result: Result<CouncilDiff, CanisterError> =
+        env.proxy_update("set_council", (vec![alice.clone(), bob.clone()],));
+    result.unwrap();
+
+    let own: Result<CouncilMember, CanisterError> =
+        env.update(env.proxy, alice_principal, "get_my_council_member", ());
+    assert_eq!(own, Ok(alice.clone()));
+    let stranger: Result<CouncilMember, CanisterError> =
+        env.update(env.proxy, env.controller, "get_my_council_member", ());
+    assert_eq!(stranger, Err(CanisterError::Unauthorized));
+
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        alice_principal,
+        "set_my_display_name",
+        ("Bob".to_string(),),
+    );
+    assert_eq!(
+        result,
+        Err(CanisterError::DuplicateCouncilMember("bob".to_string()))
+    );
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        alice_principal,
+        "set_my_display_name",
+        ("Alice A.".to_string(),),
+    );
+    result.unwrap();
+
+    // neurons of other members cannot be claimed
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        alice_principal,
+        "set_my_neuron_ids",
+        (neuron(3), vec![neuron(2)]),
+    );
+    assert_eq!(
+        result,
+        Err(CanisterError::DuplicateCouncilMember("bob".to_string()))
+    );
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        alice_principal,
+        "set_my_neuron_ids",
+        (neuron(3), vec![neuron(4)]),
+    );
+    result.unwrap();
+
+    let mut expected = alice;
+    expected.name = "Alice A.".to_string();
+    expected.neuron_id = neuron(3);
+    expected.additional_neuron_ids = Some(vec![neuron(4)]);
+    assert_eq!(council(&env), vec![expected, bob]);
+}
+
+#[test]
+fn delegations_and_veto_rights_follow_council_changes() {
+    let env = TestEnv::new();
+    env.configure();
+    for byte in 1..=4 {
+        env.add_neuron(&neuron(byte));
+    }
+    let alice_principal = Principal::from_slice(&[11; 29]);
+    let mut alice = CouncilMember::new("alice", &neuron(1));
+    alice.principal = Some(alice_principal);
+    let members = vec![
+        alice,
+        CouncilMember::new("bob", &neuron(2)),
+        CouncilMember::new("carol", &neuron(3)),
+    ];
+    let result: Result<CouncilDiff, CanisterError> = env.proxy_update("set_council", (members,));
+    result.unwrap();
+
+    let now = env.now_seconds();
+    for (delegator, delegate) in [(neuron(2), neuron(1)), (neuron(3), neuron(1))] {
+        let result: Result<(), CanisterError> = env.proxy_update(
+            "set_delegation",
+            (Delegation {
+                delegator,
+                delegate,
+                starts_at: now + VOTING_PERIOD_SECONDS,
+                ends_at: now + 2 * VOTING_PERIOD_SECONDS,
+            },),
+        );
+        result.unwrap();
+    }
+    for byte in [1, 3] {
+        let result: Result<(), CanisterError> = env.proxy_update(
+            "set_veto_right",
+            (VetoRight {
+                neuron_id: neuron(byte),
+                action_types: vec![3],
+            },),
+        );
+        result.unwrap();
+    }
+
+    // delegations to alice and alice's veto right move to alice's new primary neuron
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        alice_principal,
+        "set_my_neuron_ids",
+        (neuron(4), Vec::<String>::new()),
+    );
+    result.unwrap();
+    // carol's delegation and veto right are dropped once carol leaves
+    let result: Result<(), CanisterError> = env.proxy_update("remove_council_member", (neuron(3),));
+    result.unwrap();
+
+    let delegations: Vec<Delegation> = env.query(env.proxy, "get_delegations", ());
+    let delegations: Vec<(String, String)> = delegations
+        .into_iter()
+        .map(|delegation| (delegation.delegator, delegation.delegate))
+        .collect();
+    assert_eq!(delegations, vec![(neuron(2), neuron(4))]);
+    let rights: Vec<VetoRight> = env.query(env.proxy, "get_veto_rights", ());
+    let holders: Vec<String> = rights.into_iter().map(|right| right.neuron_id).collect();
+    assert_eq!(holders, vec![neuron(4)]);
+}
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use integration_tests::{
+    neuron, proposal,
+    types::{
+        CanisterError, DecisionLogPage, FilterAction, FilterMatcher, MemberStats,
+        ParticipationStatus,
+    },
+    TestEnv, VOTING_PERIOD_SECONDS,
+};
+
+fn decision_log(env: &TestEnv) -> DecisionLogPage {
+    env.query(env.proxy, "get_decision_log", (0_u64, 10_u64))
+}
+
+#[test]
+fn decisions_form_a_certified_hash_chain() {
+    let env = TestEnv::with_neuron();
+    env.add_council_member("alice", &neuron(1));
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.add_proposal(&proposal(3, 3, "Fund the treasury"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(3, &neuron(1), 2);
+    env.advance_time(Duration::from_secs(VOTING_PERIOD_SECONDS));
+
+    let log = decision_log(&env);
+    assert_eq!(log.total, 2);
+    let first = &log.entries[0];
+    let second = &log.entries[1];
+    assert_eq!(first.record.proposal_id, 2);
+    assert_eq!(first.record.vote, ParticipationStatus::VotedFor);
+    assert_eq!(second.record.vote, ParticipationStatus::VotedAgainst);
+    assert_eq!(first.previous_hash, vec![0; 32]);
+    assert_eq!(second.previous_hash, first.hash);
+    assert_eq!(log.tip_hash, second.hash);
+    assert!(log.certificate.is_some());
+
+    // clearing the history leaves the decision log intact
+    let result: Result<(), CanisterError> = env.proxy_update("clear_proposal_history", ());
+    result.unwrap();
+    assert!(env.proposal_history().is_empty());
+    assert_eq!(decision_log(&env).entries.len(), 2);
+}
+
+#[test]
+fn abstentions_are_logged_once() {
+    let env = TestEnv::with_neuron();
+    env.add_council_member("alice", &neuron(1));
+    let result: Result<u64, CanisterError> = env.proxy_update(
+        "add_filter_rule",
+        (
+            FilterMatcher::TitlePrefix("Poll".to_string()),
+            FilterAction::ForceAbstain,
+        ),
+    );
+    result.unwrap();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Poll: new logo"));
+    env.watch_proposals(&baseline).unwrap();
+
+    // scanning again finds the proposal a second time
+    let result: Result<(), CanisterError> = env.proxy_update("stop_timers", ());
+    result.unwrap();
+    env.watch_proposals(&baseline).unwrap();
+
+    let log = decision_log(&env);
+    assert_eq!(log.total, 1);
+    assert_eq!(log.entries[0].record.proposal_id, 2);
+    assert_eq!(log.entries[0].record.vote, ParticipationStatus::Abstained);
+    assert_eq!(env.proposal_history().len(), 1);
+    // abstentions do not count as votes in the council stats
+    assert!(council_stats(&env, None, None).is_empty());
+}
+
+fn council_stats(env: &TestEnv, from: Option<u64>, to: Option<u64>) -> Vec<MemberStats> {
+    env.query(env.proxy, "get_council_stats", (from, to))
+}
+
+#[test]
+fn council_stats_count_each_members_participation() {
+    let env = TestEnv::with_neuron();
+    env.add_council_member("alice", &neuron(1));
+    env.add_council_member("bob", &neuron(2));
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.add_proposal(&proposal(3, 3, "Fund the treasury"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    // without bob, the quorum is not reached and the proxy votes no
+    env.set_ballot(3, &neuron(1), 1);
+    env.advance_time(Duration::from_secs(VOTING_PERIOD_SECONDS));
+
+    let stats = council_stats(&env, None, None);
+    let counts: Vec<(&str, u64, u64, u64, u64, u64)> = stats
+        .iter()
+        .map(|member| {
+            (
+                member.name.as_str(),
+                member.eligible,
+                member.voted,
+                member.yes,
+                member.no,
+                member.matched_proxy_vote,
+            )
+        })
+        .collect();
+    assert_eq!(
+        counts,
+        vec![("alice", 2, 2, 2, 0, 1), ("bob", 2, 1, 1, 0, 1)]
+    );
+
+    let log = decision_log(&env);
+    let first = log.entries[0].record.decided_at_seconds;
+    let last = log.entries[1].record.decided_at_seconds;
+    assert_eq!(council_stats(&env, Some(first), Some(last)), stats);
+    assert!(council_stats(&env, Some(last + 1), None).is_empty());
+    assert!(council_stats(&env, None, Some(first - 1)).is_empty());
+}
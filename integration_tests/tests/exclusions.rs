@@ -0,0 +1,161 @@
+use ic_sns_governance::pb::v1::{
+    nervous_system_function::FunctionType, GenericNervousSystemFunction, NervousSystemFunction,
+};
+use integration_tests::{
+    proposal,
+    types::{ActionType, CanisterError, ExclusionMode, FlaggedActionHandling},
+    TestEnv,
+};
+
+fn watched_ids(env: &TestEnv) -> Vec<u64> {
+    let mut ids: Vec<u64> = env
+        .proposal_watchlist()
+        .iter()
+        .map(|proposal| proposal.id.id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+fn call(env: &TestEnv, method: &str, action: u64) -> Result<(), CanisterError> {
+    env.proxy_update(method, (action,))
+}
+
+fn ids(action_types: Vec<ActionType>) -> Vec<u64> {
+    action_types.iter().map(|action| action.id).collect()
+}
+
+fn add_generic_function(env: &TestEnv, id: u64, name: &str) {
+    let function = NervousSystemFunction {
+        id,
+        name: name.to_string(),
+        description: Some(format!("{} description", name)),
+        function_type: Some(FunctionType::GenericNervousSystemFunction(
+            GenericNervousSystemFunction::default(),
+        )),
+    };
+    env.update_unit(
+        env.governance,
+        env.controller,
+        "add_nervous_system_function",
+        (function,),
+    );
+}
+
+#[test]
+fn allowlist_mode_only_watches_allowed_actions() {
+    let env = TestEnv::with_neuron();
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_exclusion_mode", (ExclusionMode::Allowlist,));
+    result.unwrap();
+    call(&env, "allow_action_type", 3).unwrap();
+    call(&env, "allow_action_type", 9).unwrap();
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.add_proposal(&proposal(3, 1, "Motion"));
+    env.add_proposal(&proposal(4, 9, "Treasury transfer"));
+    // a generic function that is not registered in the SNS yet is not excluded by the query
+    env.add_proposal(&proposal(5, 1000, "Generic function"));
+    env.watch_proposals(&baseline).unwrap();
+
+    assert_eq!(watched_ids(&env), vec![2, 4]);
+    let allowed: Vec<ActionType> = env.query(env.proxy, "get_allowed_action_types", ());
+    assert_eq!(ids(allowed), vec![3, 9]);
+    // the denylist is kept separately
+    assert!(env
+        .query::<Vec<ActionType>>(env.proxy, "get_exclusion_list", ())
+        .is_empty());
+
+    // disallowing in allowlist mode removes the action and purges the watchlist
+    call(&env, "disallow_action_type", 9).unwrap();
+    assert_eq!(watched_ids(&env), vec![2]);
+}
+
+#[test]
+fn switching_modes_purges_the_watchlist() {
+    let env = TestEnv::with_neuron();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.add_proposal(&proposal(3, 1, "Motion"));
+    env.watch_proposals(&baseline).unwrap();
+    assert_eq!(watched_ids(&env), vec![2, 3]);
+
+    call(&env, "disallow_action_type", 1).unwrap();
+    assert_eq!(watched_ids(&env), vec![2]);
+
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_exclusion_mode", (ExclusionMode::Allowlist,));
+    result.unwrap();
+    assert!(watched_ids(&env).is_empty());
+    assert_eq!(
+        env.query::<ExclusionMode>(env.proxy, "get_exclusion_mode", ()),
+        ExclusionMode::Allowlist
+    );
+}
+
+#[test]
+fn only_registered_functions_can_be_listed_once() {
+    let env = TestEnv::with_neuron();
+
+    assert_eq!(
+        call(&env, "disallow_action_type", 1000),
+        Err(CanisterError::UnknownActionType(1000))
+    );
+    call(&env, "disallow_action_type", 9).unwrap();
+    assert_eq!(
+        call(&env, "disallow_action_type", 9),
+        Err(CanisterError::ActionTypeAlreadyListed(9))
+    );
+
+    let excluded: Vec<ActionType> = env.query(env.proxy, "get_exclusion_list", ());
+    assert_eq!(excluded.len(), 1);
+    assert_eq!(
+        excluded[0].name,
+        Some("Transfer SNS treasury funds".to_string())
+    );
+    assert!(!excluded[0].is_generic);
+}
+
+#[test]
+fn generic_functions_registered_later_are_flagged() {
+    let env = TestEnv::with_neuron();
+    add_generic_function(&env, 1000, "Existing generic function");
+    let baseline = env.add_baseline_proposal();
+    env.watch_proposals(&baseline).unwrap();
+
+    // known since the first fetch, so not flagged
+    let action_types: Vec<ActionType> = env.query(env.proxy, "get_action_types", ());
+    assert!(action_types.iter().all(|action| !action.flagged));
+
+    let result: Result<(), CanisterError> = env.proxy_update(
+        "set_flagged_action_handling",
+        (FlaggedActionHandling::Ignore,),
+    );
+    result.unwrap();
+    add_generic_function(&env, 1001, "New generic function");
+    let refreshed: Result<Vec<ActionType>, CanisterError> =
+        env.proxy_update("refresh_action_types", ());
+    let flagged: Vec<ActionType> = refreshed
+        .unwrap()
+        .into_iter()
+        .filter(|action| action.flagged)
+        .collect();
+    assert_eq!(ids(flagged.clone()), vec![1001]);
+    assert!(flagged[0].is_generic);
+    assert_eq!(
+        flagged[0].description,
+        Some("New generic function description".to_string())
+    );
+
+    env.advance_time(std::time::Duration::from_secs(3600));
+    env.add_proposal(&proposal(2, 1001, "Call the new function"));
+    env.add_proposal(&proposal(3, 1000, "Call the known function"));
+    env.advance_time(std::time::Duration::from_secs(86_400));
+    assert_eq!(watched_ids(&env), vec![3]);
+
+    // reviewing the action clears the flag
+    call(&env, "acknowledge_action_type", 1001).unwrap();
+    let action_types: Vec<ActionType> = env.query(env.proxy, "get_action_types", ());
+    assert!(action_types.iter().all(|action| !action.flagged));
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use integration_tests::{
+    neuron, proposal,
+    types::{
+        CanisterError, FilterAction, FilterMatcher, FilterRule, FilterSample, MockProposal,
+        ParticipationStatus, VotingPolicy,
+    },
+    TestEnv, VOTING_PERIOD_SECONDS,
+};
+
+fn add_rule(env: &TestEnv, matcher: FilterMatcher, action: FilterAction) -> u64 {
+    let result: Result<u64, CanisterError> = env.proxy_update("add_filter_rule", (matcher, action));
+    result.unwrap()
+}
+
+#[test]
+fn rules_skip_abstain_or_assign_a_policy() {
+    let env = TestEnv::with_neuron();
+    env.add_council_member("alice", &neuron(1));
+    env.add_council_member("bob", &neuron(2));
+    // follows a single yes vote, which the default policy would not
+    let policy = VotingPolicy {
+        name: "lenient".to_string(),
+        quorum_percent: 0,
+        approval_percent: 0,
+    };
+    let result: Result<(), CanisterError> = env.proxy_update("set_voting_policy", (policy,));
+    result.unwrap();
+    add_rule(
+        &env,
+        FilterMatcher::SummaryKeyword("treasury".to_string()),
+        FilterAction::ApplyPolicy("lenient".to_string()),
+    );
+    add_rule(
+        &env,
+        FilterMatcher::UrlDomain("example.org".to_string()),
+        FilterAction::ForceAbstain,
+    );
+    add_rule(
+        &env,
+        FilterMatcher::ProposerNeuron(neuron(0xbb)),
+        FilterAction::Skip,
+    );
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&MockProposal {
+        summary: "Move TREASURY funds".to_string(),
+        ..proposal(2, 3, "Transfer")
+    });
+    env.add_proposal(&MockProposal {
+        url: "https://forum.example.org/t/1".to_string(),
+        ..proposal(3, 3, "Forum proposal")
+    });
+    env.add_proposal(&MockProposal {
+        proposer: Some(neuron(0xbb)),
+        ..proposal(4, 3, "Spam")
+    });
+    env.watch_proposals(&baseline).unwrap();
+
+    let watchlist = env.proposal_watchlist();
+    assert_eq!(watchlist.len(), 1);
+    assert_eq!(watchlist[0].id.id, 2);
+    assert_eq!(watchlist[0].policy, Some("lenient".to_string()));
+
+    let history = env.proposal_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id.id, 3);
+    assert_eq!(
+        history[0].participation_status,
+        ParticipationStatus::Abstained
+    );
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.advance_time(Duration::from_secs(VOTING_PERIOD_SECONDS));
+    assert_eq!(
+        env.proposal_history()[1].participation_status,
+        ParticipationStatus::VotedFor
+    );
+}
+
+#[test]
+fn test_filter_rules_returns_the_first_matching_rule() {
+    let env = TestEnv::new();
+    let rule_id = add_rule(
+        &env,
+        FilterMatcher::TitleContains("COUNCIL".to_string()),
+        FilterAction::ForceAbstain,
+    );
+    let sample = FilterSample {
+        title: "CONFIGURE COUNCIL NEURON: add dave".to_string(),
+        summary: String::new(),
+        url: String::new(),
+        proposer: None,
+    };
+
+    // the default rule was added first
+    let matched: Option<FilterRule> = env.query(env.proxy, "test_filter_rules", (sample.clone(),));
+    assert_eq!(matched.unwrap().action, FilterAction::Skip);
+
+    let removed: Result<(), CanisterError> = env.proxy_update("remove_filter_rule", (0_u64,));
+    removed.unwrap();
+    let matched: Option<FilterRule> = env.query(env.proxy, "test_filter_rules", (sample,));
+    assert_eq!(matched.unwrap().id, rule_id);
+}
+
+#[test]
+fn rule_ids_are_not_reused_and_proposers_are_normalized() {
+    let env = TestEnv::new();
+    let first = add_rule(
+        &env,
+        FilterMatcher::ProposerNeuron(neuron(0xbb).to_uppercase()),
+        FilterAction::Skip,
+    );
+    let rules: Vec<FilterRule> = env.query(env.proxy, "get_filter_rules", ());
+    assert_eq!(
+        rules.last().unwrap().matcher,
+        FilterMatcher::ProposerNeuron(neuron(0xbb))
+    );
+
+    let removed: Result<(), CanisterError> = env.proxy_update("remove_filter_rule", (first,));
+    removed.unwrap();
+    let second = add_rule(
+        &env,
+        FilterMatcher::TitlePrefix("x".to_string()),
+        FilterAction::Skip,
+    );
+    assert!(second > first);
+
+    let result: Result<u64, CanisterError> = env.proxy_update(
+        "add_filter_rule",
+        (
+            FilterMatcher::ProposerNeuron("bb".to_string()),
+            FilterAction::Skip,
+        ),
+    );
+    assert_eq!(
+        result,
+        Err(CanisterError::InvalidNeuronId("bb".to_string()))
+    );
+}
+
+#[test]
+fn rules_cannot_reference_unknown_policies() {
+    let env = TestEnv::new();
+    let result: Result<u64, CanisterError> = env.proxy_update(
+        "add_filter_rule",
+        (
+            FilterMatcher::TitlePrefix("x".to_string()),
+            FilterAction::ApplyPolicy("missing".to_string()),
+        ),
+    );
+    assert_eq!(
+        result,
+        Err(CanisterError::VotingPolicyNotFound("missing".to_string()))
+    );
+}
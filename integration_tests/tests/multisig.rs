@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use integration_tests::{
+    neuron,
+    types::{
+        ApprovalOutcome, CanisterError, CouncilMember, MultisigPolicy, PendingAction,
+        SensitiveAction,
+    },
+    Principal, TestEnv,
+};
+
+const EXPIRY_SECONDS: u64 = 3600;
+
+fn set_policy(env: &TestEnv, signer: Principal) {
+    let policy = MultisigPolicy {
+        signers: vec![env.controller, signer],
+        threshold: 2,
+        expiry_seconds: EXPIRY_SECONDS,
+    };
+    let result: Result<(), CanisterError> = env.proxy_update("set_multisig_policy", (policy,));
+    result.unwrap();
+}
+
+fn submit(env: &TestEnv, action: SensitiveAction) -> Result<ApprovalOutcome, CanisterError> {
+    env.proxy_update("submit_action", (action,))
+}
+
+fn approve(env: &TestEnv, sender: Principal, id: u64) -> Result<ApprovalOutcome, CanisterError> {
+    env.update(env.proxy, sender, "approve_action", (id,))
+}
+
+#[test]
+fn sensitive_operations_execute_once_approved() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    let signer = Principal::from_slice(&[31; 29]);
+    let stranger = Principal::from_slice(&[32; 29]);
+    set_policy(&env, signer);
+
+    let result: Result<(), CanisterError> = env.proxy_update("emergency_reset", ());
+    assert_eq!(result, Err(CanisterError::ApprovalRequired));
+
+    let outcome = submit(&env, SensitiveAction::EmergencyReset).unwrap();
+    assert_eq!(
+        outcome,
+        ApprovalOutcome::Pending {
+            id: 0,
+            approvals: 1
+        }
+    );
+    assert_eq!(
+        approve(&env, env.controller, 0),
+        Err(CanisterError::AlreadyApproved(0))
+    );
+    assert_eq!(approve(&env, stranger, 0), Err(CanisterError::Unauthorized));
+    let council: Vec<CouncilMember> = env.query(env.proxy, "get_council", ());
+    assert_eq!(council.len(), 1);
+
+    assert_eq!(
+        approve(&env, signer, 0),
+        Ok(ApprovalOutcome::Executed { id: 0 })
+    );
+    let council: Vec<CouncilMember> = env.query(env.proxy, "get_council", ());
+    assert!(council.is_empty());
+    let pending: Vec<PendingAction> = env.query(env.proxy, "get_pending_actions", ());
+    assert!(pending.is_empty());
+}
+
+#[test]
+fn pending_operations_expire() {
+    let env = TestEnv::new();
+    let signer = Principal::from_slice(&[31; 29]);
+    set_policy(&env, signer);
+
+    submit(&env, SensitiveAction::SetGovernanceId(signer)).unwrap();
+    let pending: Vec<PendingAction> = env.query(env.proxy, "get_pending_actions", ());
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].approvals, vec![env.controller]);
+
+    env.advance_time(Duration::from_secs(EXPIRY_SECONDS));
+    let pending: Vec<PendingAction> = env.query(env.proxy, "get_pending_actions", ());
+    assert!(pending.is_empty());
+    assert_eq!(
+        approve(&env, signer, 0),
+        Err(CanisterError::PendingActionNotFound(0))
+    );
+
+    // the policy can only be removed with the signers' approval
+    let result: Result<(), CanisterError> = env.proxy_update(
+        "set_multisig_policy",
+        (MultisigPolicy {
+            signers: vec![env.controller],
+            threshold: 1,
+            expiry_seconds: EXPIRY_SECONDS,
+        },),
+    );
+    assert_eq!(result, Err(CanisterError::ApprovalRequired));
+    submit(&env, SensitiveAction::SetMultisigPolicy(None)).unwrap();
+    approve(&env, signer, 1).unwrap();
+    let policy: Option<MultisigPolicy> = env.query(env.proxy, "get_multisig_policy", ());
+    assert_eq!(policy, None);
+}
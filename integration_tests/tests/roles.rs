@@ -0,0 +1,90 @@
+use integration_tests::{
+    types::{CanisterError, FilterAction, FilterMatcher, Role, RoleAssignment},
+    Principal, TestEnv,
+};
+
+fn grant(env: &TestEnv, principal: Principal, role: Role) -> Result<(), CanisterError> {
+    env.proxy_update("grant_role", (principal, role))
+}
+
+fn revoke(env: &TestEnv, principal: Principal, role: Role) -> Result<(), CanisterError> {
+    env.proxy_update("revoke_role", (principal, role))
+}
+
+fn add_rule(env: &TestEnv, sender: Principal) -> Result<u64, CanisterError> {
+    env.update(
+        env.proxy,
+        sender,
+        "add_filter_rule",
+        (
+            FilterMatcher::TitlePrefix("x".to_string()),
+            FilterAction::ForceAbstain,
+        ),
+    )
+}
+
+#[test]
+fn operators_handle_routine_operations_only() {
+    let env = TestEnv::new();
+    let operator = Principal::from_slice(&[21; 29]);
+
+    assert_eq!(add_rule(&env, operator), Err(CanisterError::Unauthorized));
+    grant(&env, operator, Role::Operator).unwrap();
+    add_rule(&env, operator).unwrap();
+
+    let result: Result<(), CanisterError> =
+        env.update(env.proxy, operator, "set_governance_id", (operator,));
+    assert_eq!(result, Err(CanisterError::Unauthorized));
+    grant(&env, operator, Role::Admin).unwrap();
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        operator,
+        "revoke_role",
+        (operator, Role::Operator),
+    );
+    result.unwrap();
+    let roles: Vec<Role> = env.update(env.proxy, operator, "get_my_roles", ());
+    assert_eq!(roles, vec![Role::Admin]);
+}
+
+#[test]
+fn auditors_read_assignments_and_revocation_is_checked() {
+    let env = TestEnv::new();
+    let operator = Principal::from_slice(&[21; 29]);
+    let auditor = Principal::from_slice(&[22; 29]);
+    grant(&env, operator, Role::Operator).unwrap();
+    grant(&env, auditor, Role::Auditor).unwrap();
+    // granting twice keeps a single assignment
+    grant(&env, auditor, Role::Auditor).unwrap();
+    assert_eq!(
+        grant(&env, Principal::anonymous(), Role::Auditor),
+        Err(CanisterError::ConfigurationError)
+    );
+
+    let assignments: Result<Vec<RoleAssignment>, CanisterError> =
+        env.update(env.proxy, auditor, "get_role_assignments", ());
+    assert_eq!(
+        assignments,
+        Ok(vec![
+            RoleAssignment {
+                principal: operator,
+                role: Role::Operator,
+            },
+            RoleAssignment {
+                principal: auditor,
+                role: Role::Auditor,
+            },
+        ])
+    );
+    assert_eq!(add_rule(&env, auditor), Err(CanisterError::Unauthorized));
+    let stranger: Result<Vec<RoleAssignment>, CanisterError> =
+        env.query(env.proxy, "get_role_assignments", ());
+    assert_eq!(stranger, Err(CanisterError::Unauthorized));
+
+    revoke(&env, operator, Role::Operator).unwrap();
+    assert_eq!(add_rule(&env, operator), Err(CanisterError::Unauthorized));
+    assert_eq!(
+        revoke(&env, operator, Role::Operator),
+        Err(CanisterError::RoleAssignmentNotFound)
+    );
+}
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use integration_tests::{
+    neuron,
+    types::{
+        CanisterError, ConfigChange, CouncilMember, FilterAction, FilterMatcher, FilterRule,
+        QueuedChange, VetoRight, VotingPolicy,
+    },
+    TestEnv,
+};
+
+const DELAY_SECONDS: u64 = 86400;
+
+fn set_delay(env: &TestEnv) {
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_timelock_delay", (DELAY_SECONDS,));
+    result.unwrap();
+}
+
+fn queue(env: &TestEnv, change: ConfigChange) -> Result<u64, CanisterError> {
+    env.proxy_update("queue_change", (change,))
+}
+
+fn queued_changes(env: &TestEnv) -> Vec<QueuedChange> {
+    env.query(env.proxy, "get_queued_changes", ())
+}
+
+#[test]
+fn queued_changes_take_effect_after_the_delay() {
+    let env = TestEnv::new();
+    set_delay(&env);
+    let policy = VotingPolicy {
+        name: "strict".to_string(),
+        quorum_percent: 90,
+        approval_percent: 90,
+    };
+
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_voting_policy", (policy.clone(),));
+    assert_eq!(result, Err(CanisterError::TimelockRequired));
+    let result: Result<(), CanisterError> = env.proxy_update("set_timelock_delay", (0_u64,));
+    assert_eq!(result, Err(CanisterError::TimelockRequired));
+
+    let id = queue(&env, ConfigChange::SetVotingPolicy(policy.clone())).unwrap();
+    let queued = queued_changes(&env);
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].id, id);
+    assert_eq!(
+        queued[0].activates_at_seconds - queued[0].queued_at_seconds,
+        DELAY_SECONDS
+    );
+
+    env.advance_time(Duration::from_secs(DELAY_SECONDS - 60));
+    let policies: Vec<VotingPolicy> = env.query(env.proxy, "get_voting_policies", ());
+    assert!(!policies.contains(&policy));
+
+    env.advance_time(Duration::from_secs(60));
+    let policies: Vec<VotingPolicy> = env.query(env.proxy, "get_voting_policies", ());
+    assert!(policies.contains(&policy));
+    assert!(queued_changes(&env).is_empty());
+}
+
+#[test]
+fn queued_changes_can_be_cancelled() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    set_delay(&env);
+
+    let id = queue(&env, ConfigChange::RemoveCouncilMember(neuron(1))).unwrap();
+    let result: Result<(), CanisterError> = env.proxy_update("cancel_change", (id,));
+    result.unwrap();
+    let result: Result<(), CanisterError> = env.proxy_update("cancel_change", (id,));
+    assert_eq!(result, Err(CanisterError::QueuedChangeNotFound(id)));
+
+    env.advance_time(Duration::from_secs(DELAY_SECONDS));
+    let council: Vec<CouncilMember> = env.query(env.proxy, "get_council", ());
+    assert_eq!(council, vec![CouncilMember::new("alice", &neuron(1))]);
+}
+
+#[test]
+fn veto_rights_and_filter_rules_are_timelocked() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    set_delay(&env);
+    let right = VetoRight {
+        neuron_id: neuron(1),
+        action_types: vec![3],
+    };
+    let matcher = FilterMatcher::TitlePrefix("x".to_string());
+
+    let result: Result<(), CanisterError> = env.proxy_update("set_veto_right", (right.clone(),));
+    assert_eq!(result, Err(CanisterError::TimelockRequired));
+    let result: Result<u64, CanisterError> =
+        env.proxy_update("add_filter_rule", (matcher.clone(), FilterAction::Skip));
+    assert_eq!(result, Err(CanisterError::TimelockRequired));
+
+    queue(&env, ConfigChange::SetVetoRight(right.clone())).unwrap();
+    queue(
+        &env,
+        ConfigChange::AddFilterRule {
+            matcher: matcher.clone(),
+            action: FilterAction::Skip,
+        },
+    )
+    .unwrap();
+    env.advance_time(Duration::from_secs(DELAY_SECONDS));
+
+    let rights: Vec<VetoRight> = env.query(env.proxy, "get_veto_rights", ());
+    assert_eq!(rights, vec![right]);
+    let rules: Vec<FilterRule> = env.query(env.proxy, "get_filter_rules", ());
+    assert_eq!(rules.last().unwrap().matcher, matcher);
+}
+
+#[test]
+fn emergency_reset_drops_queued_changes() {
+    let env = TestEnv::new();
+    env.configure();
+    env.add_council_member("alice", &neuron(1));
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_early_decision_interval", (Some(3600_u64),));
+    result.unwrap();
+    set_delay(&env);
+    let policy = VotingPolicy {
+        name: "strict".to_string(),
+        quorum_percent: 90,
+        approval_percent: 90,
+    };
+    queue(&env, ConfigChange::SetVotingPolicy(policy.clone())).unwrap();
+
+    let result: Result<(), CanisterError> = env.proxy_update("emergency_reset", ());
+    result.unwrap();
+
+    assert!(queued_changes(&env).is_empty());
+    let council: Vec<CouncilMember> = env.query(env.proxy, "get_council", ());
+    assert!(council.is_empty());
+    let interval: Option<u64> = env.query(env.proxy, "get_early_decision_interval", ());
+    assert_eq!(interval, None);
+    env.advance_time(Duration::from_secs(DELAY_SECONDS));
+    let policies: Vec<VotingPolicy> = env.query(env.proxy, "get_voting_policies", ());
+    assert!(!policies.contains(&policy));
+}
@@ -0,0 +1,919 @@
+use std::time::Duration;
+
+use candid::{Nat, Principal};
+use ic_sns_governance::pb::v1::{ProposalId, Vote};
+use icrc_ledger_types::icrc1::transfer::{Memo, TransferArg, TransferError};
+use integration_tests::{
+    neuron, neuron_hex, proposal,
+    types::{
+        BallotChoice, CanisterError, CouncilDiff, CouncilMember, CouncilSource, DecisionLogPage,
+        Delegation, ExclusionMode, InactivityRule, LiveTally, MemberActivity, MemberStats,
+        MockProposal, NeuronCombineRule, ParticipationStatus, ProxyProposalQuery, SkipReason,
+        SuspensionChange, SuspensionEvent, VetoRight, VoteResponse, VotingMode, VotingPolicy,
+    },
+    TestEnv, VOTING_PERIOD_SECONDS,
+};
+
+const COUNCIL: [&str; 3] = ["alice", "bob", "carol"];
+
+fn env_with_council() -> TestEnv {
+    let env = TestEnv::with_neuron();
+    for (index, name) in COUNCIL.iter().enumerate() {
+        env.add_council_member(name, &neuron(index as u8 + 1));
+    }
+    env
+}
+
+/// Time until the proxy evaluates a proposal created now: one hour before its deadline.
+fn until_evaluation() -> Duration {
+    Duration::from_secs(VOTING_PERIOD_SECONDS - 3600 + 60)
+}
+
+#[test]
+fn create_neuron_stakes_tokens_and_claims_neuron() {
+    let env = TestEnv::new();
+    env.configure();
+
+    let neuron_id = env.create_neuron(Nat::from(500_000_000_u64), 42).unwrap();
+
+    let transfers: Vec<TransferArg> = env.query(env.ledger, "get_transfers", ());
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].amount, Nat::from(500_000_000_u64));
+    assert_eq!(transfers[0].to.owner, env.governance);
+    assert_eq!(
+        transfers[0].to.subaccount.map(|s| s.to_vec()),
+        Some(neuron_id.id.clone())
+    );
+    assert_eq!(transfers[0].memo, Some(Memo::from(42_u64)));
+    assert_eq!(env.neuron_id().unwrap(), neuron_id);
+
+    // a second neuron cannot be created
+    assert_eq!(
+        env.create_neuron(Nat::from(1_u64), 43),
+        Err(CanisterError::NeuronAlreadySet)
+    );
+}
+
+#[test]
+fn create_neuron_fails_on_ledger_error() {
+    let env = TestEnv::new();
+    env.configure();
+    env.update_unit(
+        env.ledger,
+        env.controller,
+        "set_transfer_error",
+        (Some(TransferError::TemporarilyUnavailable),),
+    );
+
+    let result = env.create_neuron(Nat::from(500_000_000_u64), 42);
+
+    assert!(matches!(result, Err(CanisterError::Unknown(_))));
+    assert!(env.neuron_id().is_err());
+}
+
+#[test]
+fn watch_proposals_requires_a_neuron() {
+    let env = TestEnv::new();
+    env.configure();
+    let baseline = env.add_baseline_proposal();
+
+    assert!(env.watch_proposals(&baseline).is_err());
+    assert!(!env.query::<bool>(env.proxy, "get_watching_status", ()));
+}
+
+#[test]
+fn votes_for_when_council_majority_votes_yes() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+
+    env.watch_proposals(&baseline).unwrap();
+
+    let watchlist = env.proposal_watchlist();
+    assert_eq!(watchlist.len(), 1);
+    assert_eq!(watchlist[0].id.id, 2);
+    assert!(watchlist[0].timer_scheduled_for.is_some());
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(until_evaluation());
+
+    let proxy_neuron = neuron_hex(&env.neuron_id().unwrap());
+    let votes = env.registered_votes();
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].proposal_id, 2);
+    assert_eq!(votes[0].neuron_id, proxy_neuron);
+    assert_eq!(votes[0].vote, Vote::Yes as i32);
+
+    let history = env.proposal_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(
+        history[0].participation_status,
+        ParticipationStatus::VotedFor
+    );
+    assert!(env.proposal_watchlist().is_empty());
+}
+
+#[test]
+fn votes_against_without_council_quorum() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.advance_time(until_evaluation());
+
+    let votes = env.registered_votes();
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].vote, Vote::No as i32);
+    assert_eq!(
+        env.proposal_history()[0].participation_status,
+        ParticipationStatus::VotedAgainst
+    );
+}
+
+#[test]
+fn votes_against_when_council_majority_votes_no() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 2);
+    env.set_ballot(2, &neuron(3), 2);
+    env.advance_time(until_evaluation());
+
+    assert_eq!(env.registered_votes()[0].vote, Vote::No as i32);
+    assert_eq!(
+        env.proposal_history()[0].participation_status,
+        ParticipationStatus::VotedAgainst
+    );
+}
+
+#[test]
+fn does_not_vote_on_closed_proposals() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.close_proposal(2);
+    env.advance_time(until_evaluation());
+
+    assert!(env.registered_votes().is_empty());
+    assert_eq!(
+        env.proposal_history()[0].participation_status,
+        ParticipationStatus::TooLateToParticipate
+    );
+}
+
+#[test]
+fn ignores_excluded_actions_and_council_configuration_proposals() {
+    let env = env_with_council();
+    let excluded: Result<(), CanisterError> = env.proxy_update("disallow_action_type", (5_u64,));
+    excluded.unwrap();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.add_proposal(&proposal(3, 5, "Excluded action"));
+    env.add_proposal(&proposal(4, 3, "CONFIGURE COUNCIL NEURON: add dave"));
+
+    env.watch_proposals(&baseline).unwrap();
+
+    let watchlist = env.proposal_watchlist();
+    assert_eq!(watchlist.len(), 1);
+    assert_eq!(watchlist[0].id.id, 2);
+}
+
+#[test]
+fn picks_up_new_proposals_on_the_next_daily_cycle() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.advance_time(Duration::from_secs(3600));
+    env.add_proposal(&proposal(3, 3, "Treasury transfer"));
+    assert_eq!(env.proposal_watchlist().len(), 1);
+
+    env.advance_time(Duration::from_secs(86_400));
+
+    let mut watched: Vec<u64> = env
+        .proposal_watchlist()
+        .iter()
+        .map(|proposal| proposal.id.id)
+        .collect();
+    watched.sort();
+    assert_eq!(watched, vec![2, 3]);
+}
+
+#[test]
+fn records_skipped_proposals_with_their_reason() {
+    let env = env_with_council();
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_exclusion_mode", (ExclusionMode::Allowlist,));
+    result.unwrap();
+    let result: Result<(), CanisterError> = env.proxy_update("allow_action_type", (3_u64,));
+    result.unwrap();
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.close_proposal(2);
+    env.add_proposal(&MockProposal {
+        creation_timestamp_seconds: Some(env.now_seconds() - 3600),
+        initial_voting_period_seconds: 3600,
+        ..proposal(3, 3, "Short voting period")
+    });
+    // not registered in the SNS
+    env.add_proposal(&proposal(4, 1000, "Generic function"));
+    env.add_proposal(&proposal(5, 3, "CONFIGURE COUNCIL NEURON: add dave"));
+    env.add_proposal(&proposal(6, 3, "Upgrade the frontend"));
+    // registered, but not allowed
+    env.add_proposal(&proposal(7, 1, "Poll: new logo"));
+    env.watch_proposals(&baseline).unwrap();
+
+    let skipped: Vec<ProxyProposalQuery> = env.query(env.proxy, "get_skipped_proposals", ());
+    let reasons: Vec<(u64, ParticipationStatus)> = skipped
+        .into_iter()
+        .map(|proposal| (proposal.id.id, proposal.participation_status))
+        .collect();
+    let skipped_with = |reason| ParticipationStatus::Skipped { reason };
+    assert_eq!(
+        reasons,
+        vec![
+            (7, skipped_with(SkipReason::ExcludedAction)),
+            (5, skipped_with(SkipReason::FilterRule { rule_id: 0 })),
+            (4, skipped_with(SkipReason::ExcludedAction)),
+            (3, skipped_with(SkipReason::DeadlinePassed)),
+            (2, skipped_with(SkipReason::AlreadyClosed)),
+        ]
+    );
+    assert_eq!(env.proposal_watchlist().len(), 1);
+    assert!(env.proposal_history().is_empty());
+
+    let status: Option<ProxyProposalQuery> =
+        env.query(env.proxy, "get_proposal_status", (ProposalId { id: 4 },));
+    assert_eq!(
+        status.unwrap().participation_status,
+        skipped_with(SkipReason::ExcludedAction)
+    );
+}
+
+/// Shrinks the council to Alice while proposal 2 is open. Alice votes yes, which is no quorum
+/// of the council at discovery time but a majority of the shrunk council.
+fn shrink_council_while_open(env: &TestEnv) {
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+
+    let council = vec![CouncilMember::new("alice", &neuron(1))];
+    let diff: Result<CouncilDiff, CanisterError> = env.proxy_update("set_council", (council,));
+    diff.unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.advance_time(until_evaluation());
+}
+
+#[test]
+fn evaluates_against_the_council_at_discovery_time() {
+    let env = env_with_council();
+
+    shrink_council_while_open(&env);
+
+    assert_eq!(env.registered_votes()[0].vote, Vote::No as i32);
+    // epochs 1 to 3 added the initial members
+    assert_eq!(env.proposal_history()[0].council_epoch, Some(3));
+}
+
+#[test]
+fn evaluates_against_the_live_council_if_configured() {
+    let env = env_with_council();
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_council_source", (CouncilSource::Live,));
+    result.unwrap();
+
+    shrink_council_while_open(&env);
+
+    assert_eq!(env.registered_votes()[0].vote, Vote::Yes as i32);
+    assert_eq!(env.proposal_history()[0].council_epoch, Some(4));
+}
+
+#[test]
+fn neurons_without_a_ballot_do_not_count_toward_the_quorum() {
+    let env = TestEnv::with_neuron();
+    env.add_council_member("alice", &neuron(1));
+    env.add_council_member("bob", &neuron(2));
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    // created after the proposal, so without a ballot
+    env.add_council_member("carol", &neuron(3));
+    env.add_council_member("dave", &neuron(4));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(until_evaluation());
+
+    assert_eq!(env.registered_votes()[0].vote, Vote::Yes as i32);
+    let evaluation = env.proposal_history()[0].evaluation.clone().unwrap();
+    assert_eq!(evaluation.eligible_members, 2);
+    assert_eq!(evaluation.voted, 2);
+    let ineligible: Vec<String> = evaluation
+        .ineligible_members
+        .into_iter()
+        .map(|member| member.name)
+        .collect();
+    assert_eq!(ineligible, vec!["carol", "dave"]);
+}
+
+#[test]
+fn history_records_the_full_evaluation() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&MockProposal {
+        url: "https://forum.example.com/t/upgrade".to_string(),
+        ..proposal(2, 3, "Upgrade the dapp")
+    });
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 2);
+    env.advance_time(until_evaluation());
+
+    let history = env.proposal_history();
+    assert_eq!(history[0].title.as_deref(), Some("Upgrade the dapp"));
+    assert_eq!(
+        history[0].url.as_deref(),
+        Some("https://forum.example.com/t/upgrade")
+    );
+    let evaluation = history[0].evaluation.clone().unwrap();
+    assert_eq!(
+        (evaluation.yes, evaluation.no, evaluation.not_voted),
+        (1, 1, 1)
+    );
+    let ballots: Vec<(String, BallotChoice)> = evaluation
+        .member_ballots
+        .into_iter()
+        .map(|ballot| (ballot.name, ballot.vote))
+        .collect();
+    assert_eq!(
+        ballots,
+        vec![
+            ("alice".to_string(), BallotChoice::Yes),
+            ("bob".to_string(), BallotChoice::No),
+            ("carol".to_string(), BallotChoice::NotVoted),
+        ]
+    );
+    assert_eq!(evaluation.quorum_percent, Some(50));
+    assert_eq!(evaluation.approval_percent, Some(50));
+    assert!(evaluation.evaluated_at_seconds <= env.now_seconds());
+    assert_eq!(evaluation.vote_sent, Some(BallotChoice::No));
+    assert_eq!(
+        evaluation.governance_response,
+        Some(VoteResponse::Registered)
+    );
+}
+
+#[test]
+fn live_tally_projects_the_vote_without_voting() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+
+    env.set_ballot(2, &neuron(1), 1);
+    let live: Result<LiveTally, CanisterError> =
+        env.proxy_update("get_live_tally", (ProposalId { id: 2 },));
+    let live = live.unwrap();
+    assert_eq!(live.projected_vote, BallotChoice::No);
+    let pending: Vec<String> = live
+        .pending_members
+        .into_iter()
+        .map(|member| member.name)
+        .collect();
+    assert_eq!(pending, vec!["bob", "carol"]);
+    assert!(live.seconds_until_evaluation.unwrap() <= VOTING_PERIOD_SECONDS - 3600);
+
+    env.set_ballot(2, &neuron(2), 1);
+    let live: Result<LiveTally, CanisterError> =
+        env.proxy_update("get_live_tally", (ProposalId { id: 2 },));
+    let live = live.unwrap();
+    assert_eq!(live.projected_vote, BallotChoice::Yes);
+    assert_eq!((live.tally.voted, live.tally.yes), (2, 2));
+
+    assert!(env.registered_votes().is_empty());
+    assert_eq!(env.proposal_watchlist().len(), 1);
+
+    let unknown: Result<LiveTally, CanisterError> =
+        env.proxy_update("get_live_tally", (ProposalId { id: 9 },));
+    assert_eq!(
+        unknown.err(),
+        Some(CanisterError::ProposalIsNotInWatchlist(9))
+    );
+
+    let stranger = Principal::from_slice(&[51; 29]);
+    let result: Result<LiveTally, CanisterError> = env.update(
+        env.proxy,
+        stranger,
+        "get_live_tally",
+        (ProposalId { id: 2 },),
+    );
+    assert_eq!(result.err(), Some(CanisterError::Unauthorized));
+}
+
+#[test]
+fn votes_early_once_the_council_outcome_is_final() {
+    let env = env_with_council();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    let enabled: Result<(), CanisterError> =
+        env.proxy_update("set_early_decision_interval", (Some(60_u64),));
+    enabled.unwrap();
+
+    // carol could still fail the quorum
+    env.set_ballot(2, &neuron(1), 1);
+    env.advance_time(Duration::from_secs(120));
+    assert!(env.registered_votes().is_empty());
+
+    // carol can no longer change the outcome
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(Duration::from_secs(120));
+    let votes = env.registered_votes();
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].vote, Vote::Yes as i32);
+    assert!(env.proposal_watchlist().is_empty());
+
+    // the cancelled evaluation timer does not vote again
+    env.advance_time(until_evaluation());
+    assert_eq!(env.registered_votes().len(), 1);
+    assert_eq!(env.proposal_history().len(), 1);
+}
+
+#[test]
+fn early_decisions_allow_for_reinstated_members() {
+    let env = env_with_council();
+    let policy: Result<(), CanisterError> = env.proxy_update(
+        "set_voting_policy",
+        (VotingPolicy {
+            name: "default".to_string(),
+            quorum_percent: 70,
+            approval_percent: 50,
+        },),
+    );
+    policy.unwrap();
+    let rule: Result<(), CanisterError> = env.proxy_update(
+        "set_inactivity_rule",
+        (Some(InactivityRule {
+            missed_proposals: 1,
+        }),),
+    );
+    rule.unwrap();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    // found by the next daily scan, and evaluated an hour after proposal 2
+    env.advance_time(Duration::from_secs(3600));
+    env.add_proposal(&proposal(3, 3, "Fund the treasury"));
+
+    // carol misses proposal 2 and is suspended
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(until_evaluation() - Duration::from_secs(3600));
+    assert_eq!(env.proposal_history().len(), 1);
+
+    // without carol, alice and bob meet the quorum, but not if carol is reinstated without voting
+    let enabled: Result<(), CanisterError> =
+        env.proxy_update("set_early_decision_interval", (Some(60_u64),));
+    enabled.unwrap();
+    env.set_ballot(3, &neuron(1), 1);
+    env.set_ballot(3, &neuron(2), 1);
+    env.advance_time(Duration::from_secs(120));
+    assert_eq!(env.registered_votes().len(), 1);
+
+    let reinstated: Result<(), CanisterError> =
+        env.proxy_update("reinstate_council_member", (neuron(3),));
+    reinstated.unwrap();
+    env.advance_time(Duration::from_secs(3600));
+    let votes = env.registered_votes();
+    assert_eq!(votes.len(), 2);
+    assert_eq!(votes[1].proposal_id, 3);
+    assert_eq!(votes[1].vote, Vote::No as i32);
+}
+
+#[test]
+fn early_decision_interval_has_a_minimum() {
+    let env = TestEnv::with_neuron();
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_early_decision_interval", (Some(10_u64),));
+    assert_eq!(result, Err(CanisterError::InvalidEarlyDecisionInterval(10)));
+}
+
+#[test]
+fn suspends_inactive_members_until_they_vote_again() {
+    let env = TestEnv::with_neuron();
+    for (index, name) in ["alice", "bob", "carol", "dave"].iter().enumerate() {
+        env.add_council_member(name, &neuron(index as u8 + 1));
+    }
+    let rule: Result<(), CanisterError> = env.proxy_update(
+        "set_inactivity_rule",
+        (Some(InactivityRule {
+            missed_proposals: 1,
+        }),),
+    );
+    rule.unwrap();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    // found by the next daily scan, and evaluated an hour after proposal 2
+    env.advance_time(Duration::from_secs(3600));
+    env.add_proposal(&proposal(3, 3, "Fund the treasury"));
+
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(until_evaluation() - Duration::from_secs(3600));
+    assert_eq!(env.proposal_history().len(), 1);
+
+    env.set_ballot(3, &neuron(1), 1);
+    env.set_ballot(3, &neuron(2), 1);
+    env.set_ballot(3, &neuron(4), 2);
+    env.advance_time(Duration::from_secs(3600));
+
+    let history = env.proposal_history();
+    let evaluation = history[1].evaluation.clone().unwrap();
+    assert_eq!(history[1].id.id, 3);
+    assert_eq!(evaluation.eligible_members, 3);
+    let suspended: Vec<String> = evaluation
+        .suspended_members
+        .into_iter()
+        .map(|member| member.name)
+        .collect();
+    assert_eq!(suspended, vec!["carol"]);
+    assert_eq!(
+        history[1].participation_status,
+        ParticipationStatus::VotedFor
+    );
+
+    let reinstated: Result<(), CanisterError> =
+        env.proxy_update("reinstate_council_member", (neuron(3),));
+    reinstated.unwrap();
+    let again: Result<(), CanisterError> =
+        env.proxy_update("reinstate_council_member", (neuron(3),));
+    assert_eq!(again, Err(CanisterError::MemberNotSuspended(neuron(3))));
+
+    let log: Vec<SuspensionEvent> = env.query(env.proxy, "get_suspension_log", ());
+    let changes: Vec<(&str, &SuspensionChange)> = log
+        .iter()
+        .map(|event| (event.name.as_str(), &event.change))
+        .collect();
+    let suspended = SuspensionChange::Suspended {
+        missed_proposals: 1,
+    };
+    assert_eq!(
+        changes,
+        vec![
+            ("carol", &suspended),
+            ("dave", &suspended),
+            ("dave", &SuspensionChange::ReinstatedByVote),
+            ("carol", &SuspensionChange::ReinstatedBy(env.controller)),
+        ]
+    );
+}
+
+#[test]
+fn suspensions_follow_the_primary_neuron_of_a_member() {
+    let env = TestEnv::with_neuron();
+    for byte in 1..=4 {
+        env.add_neuron(&neuron(byte));
+    }
+    let carol_principal = Principal::from_slice(&[13; 29]);
+    let mut carol = CouncilMember::new("carol", &neuron(3));
+    carol.principal = Some(carol_principal);
+    let council = vec![
+        CouncilMember::new("alice", &neuron(1)),
+        CouncilMember::new("bob", &neuron(2)),
+        carol,
+    ];
+    let result: Result<CouncilDiff, CanisterError> = env.proxy_update("set_council", (council,));
+    result.unwrap();
+    let rule: Result<(), CanisterError> = env.proxy_update(
+        "set_inactivity_rule",
+        (Some(InactivityRule {
+            missed_proposals: 1,
+        }),),
+    );
+    rule.unwrap();
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(until_evaluation());
+
+    // a new primary neuron does not lift carol's suspension
+    let result: Result<(), CanisterError> = env.update(
+        env.proxy,
+        carol_principal,
+        "set_my_neuron_ids",
+        (neuron(4), Vec::<String>::new()),
+    );
+    result.unwrap();
+    let activity: Vec<MemberActivity> = env.query(env.proxy, "get_member_activity", ());
+    let carol = activity
+        .iter()
+        .find(|member| member.name == "carol")
+        .unwrap();
+    assert_eq!(carol.neuron_id, neuron(4));
+    assert!(carol.suspended_since.is_some());
+    assert_eq!(carol.missed_in_a_row, 1);
+    assert!(!activity.iter().any(|member| member.neuron_id == neuron(3)));
+
+    // bob's activity is dropped once bob leaves
+    let result: Result<(), CanisterError> = env.proxy_update("remove_council_member", (neuron(2),));
+    result.unwrap();
+    let activity: Vec<MemberActivity> = env.query(env.proxy, "get_member_activity", ());
+    let names: Vec<&str> = activity.iter().map(|member| member.name.as_str()).collect();
+    assert_eq!(names, vec!["alice", "carol"]);
+}
+
+#[test]
+fn delegated_members_are_represented_by_their_delegate() {
+    let env = env_with_council();
+    let now = env.now_seconds();
+    let delegation = Delegation {
+        delegator: neuron(3),
+        delegate: neuron(1),
+        starts_at: now,
+        ends_at: now + 2 * VOTING_PERIOD_SECONDS,
+    };
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_delegation", (delegation.clone(),));
+    result.unwrap();
+    let chained: Result<(), CanisterError> = env.proxy_update(
+        "set_delegation",
+        (Delegation {
+            delegator: neuron(2),
+            delegate: neuron(3),
+            ..delegation.clone()
+        },),
+    );
+    assert_eq!(
+        chained,
+        Err(CanisterError::InvalidDelegation(
+            "delegations cannot be chained".to_string()
+        ))
+    );
+    let delegations: Vec<Delegation> = env.query(env.proxy, "get_delegations", ());
+    assert_eq!(delegations, vec![delegation]);
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 2);
+    env.advance_time(until_evaluation());
+
+    let history = env.proposal_history();
+    assert_eq!(
+        history[0].participation_status,
+        ParticipationStatus::VotedFor
+    );
+    let evaluation = history[0].evaluation.clone().unwrap();
+    assert_eq!((evaluation.voted, evaluation.yes), (3, 2));
+    let ballots: Vec<(String, BallotChoice, Option<String>)> = evaluation
+        .member_ballots
+        .into_iter()
+        .map(|ballot| (ballot.name, ballot.vote, ballot.delegate))
+        .collect();
+    assert_eq!(
+        ballots,
+        vec![
+            ("alice".to_string(), BallotChoice::Yes, None),
+            ("bob".to_string(), BallotChoice::No, None),
+            ("carol".to_string(), BallotChoice::Yes, Some(neuron(1))),
+        ]
+    );
+
+    // the delegated ballot is not carol's own participation
+    let stats: Vec<MemberStats> =
+        env.query(env.proxy, "get_council_stats", (None::<u64>, None::<u64>));
+    let carol = stats.iter().find(|member| member.name == "carol").unwrap();
+    assert_eq!(
+        (
+            carol.eligible,
+            carol.voted,
+            carol.yes,
+            carol.matched_proxy_vote
+        ),
+        (1, 0, 0, 0)
+    );
+    assert_eq!(carol.delegated, 1);
+    let activity: Vec<MemberActivity> = env.query(env.proxy, "get_member_activity", ());
+    let carol = activity
+        .iter()
+        .find(|member| member.name == "carol")
+        .unwrap();
+    assert_eq!(carol.missed_in_a_row, 1);
+}
+
+#[test]
+fn a_veto_makes_the_proxy_vote_no() {
+    let env = env_with_council();
+    let empty: Result<(), CanisterError> = env.proxy_update(
+        "set_veto_right",
+        (VetoRight {
+            neuron_id: neuron(3),
+            action_types: vec![],
+        },),
+    );
+    assert!(matches!(empty, Err(CanisterError::InvalidVetoRight(_))));
+    let right = VetoRight {
+        neuron_id: neuron(3),
+        action_types: vec![3],
+    };
+    let result: Result<(), CanisterError> = env.proxy_update("set_veto_right", (right.clone(),));
+    result.unwrap();
+    let rights: Vec<VetoRight> = env.query(env.proxy, "get_veto_rights", ());
+    assert_eq!(rights, vec![right]);
+    let missing: Result<(), CanisterError> = env.proxy_update("remove_veto_right", (neuron(2),));
+    assert_eq!(missing, Err(CanisterError::VetoRightNotFound(neuron(2))));
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 1);
+    env.set_ballot(2, &neuron(3), 2);
+    env.advance_time(until_evaluation());
+
+    let history = env.proposal_history();
+    assert_eq!(
+        history[0].participation_status,
+        ParticipationStatus::VotedAgainst
+    );
+    let evaluation = history[0].evaluation.clone().unwrap();
+    assert_eq!((evaluation.voted, evaluation.yes), (3, 2));
+    let vetoed_by: Vec<String> = evaluation
+        .vetoed_by
+        .into_iter()
+        .map(|member| member.name)
+        .collect();
+    assert_eq!(vetoed_by, vec!["carol"]);
+    assert_eq!(evaluation.vote_sent, Some(BallotChoice::No));
+}
+
+#[test]
+fn mirror_mode_copies_the_first_listed_neuron_that_voted() {
+    let env = env_with_council();
+    let empty: Result<(), CanisterError> =
+        env.proxy_update("set_voting_mode", (VotingMode::Mirror(vec![]),));
+    assert!(matches!(empty, Err(CanisterError::InvalidVotingMode(_))));
+    let lead = neuron(5);
+    env.add_neuron(&lead);
+    let mode = VotingMode::Mirror(vec![lead.to_uppercase(), neuron(2)]);
+    let result: Result<(), CanisterError> = env.proxy_update("set_voting_mode", (mode,));
+    result.unwrap();
+    let mode: VotingMode = env.query(env.proxy, "get_voting_mode", ());
+    assert_eq!(mode, VotingMode::Mirror(vec![lead.clone(), neuron(2)]));
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    // found by the next daily scan, and evaluated an hour after proposal 2
+    env.advance_time(Duration::from_secs(3600));
+    env.add_proposal(&proposal(3, 3, "Fund the treasury"));
+
+    // the lead has not voted, so the fallback's vote is copied against the council majority
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(2), 2);
+    env.set_ballot(2, &neuron(3), 1);
+    env.advance_time(until_evaluation() - Duration::from_secs(3600));
+    // none of the mirrored neurons votes on proposal 3
+    env.set_ballot(3, &neuron(1), 1);
+    env.advance_time(Duration::from_secs(3600));
+
+    let history = env.proposal_history();
+    assert_eq!(
+        history[0].participation_status,
+        ParticipationStatus::VotedAgainst
+    );
+    let evaluation = history[0].evaluation.clone().unwrap();
+    assert_eq!(evaluation.mirrored_neuron, Some(neuron(2)));
+    assert_eq!(evaluation.mirrored_neurons, vec![lead.clone(), neuron(2)]);
+    assert_eq!((evaluation.eligible_members, evaluation.voted), (2, 1));
+    assert_eq!(evaluation.quorum_percent, None);
+    assert_eq!(history[0].policy, None);
+
+    assert_eq!(history[1].id.id, 3);
+    assert_eq!(
+        history[1].participation_status,
+        ParticipationStatus::Abstained
+    );
+    assert_eq!(history[1].evaluation.clone().unwrap().mirrored_neuron, None);
+    assert_eq!(history[1].policy, None);
+    let log: DecisionLogPage = env.query(env.proxy, "get_decision_log", (0_u64, 10_u64));
+    assert_eq!(log.total, 2);
+    assert!(log
+        .entries
+        .iter()
+        .all(|entry| entry.record.policy.is_none()));
+    let votes: Vec<u64> = env
+        .registered_votes()
+        .iter()
+        .map(|vote| vote.proposal_id)
+        .collect();
+    assert_eq!(votes, vec![2]);
+}
+
+#[test]
+fn veto_rights_apply_in_mirror_mode() {
+    let env = env_with_council();
+    let right = VetoRight {
+        neuron_id: neuron(3),
+        action_types: vec![3],
+    };
+    let result: Result<(), CanisterError> = env.proxy_update("set_veto_right", (right,));
+    result.unwrap();
+    let result: Result<(), CanisterError> =
+        env.proxy_update("set_voting_mode", (VotingMode::Mirror(vec![neuron(1)]),));
+    result.unwrap();
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(3), 2);
+    env.advance_time(until_evaluation());
+
+    let history = env.proposal_history();
+    assert_eq!(
+        history[0].participation_status,
+        ParticipationStatus::VotedAgainst
+    );
+    let evaluation = history[0].evaluation.clone().unwrap();
+    assert_eq!(evaluation.mirrored_neuron, Some(neuron(1)));
+    let vetoed_by: Vec<String> = evaluation
+        .vetoed_by
+        .into_iter()
+        .map(|member| member.name)
+        .collect();
+    assert_eq!(vetoed_by, vec!["carol"]);
+    assert_eq!(env.registered_votes()[0].vote, Vote::No as i32);
+}
+
+/// Alice votes with three neurons, one yes and two no, and Bob votes yes. Carol does not vote.
+fn vote_with_several_neurons(rule: NeuronCombineRule) -> TestEnv {
+    let env = TestEnv::with_neuron();
+    let mut alice = CouncilMember::new("alice", &neuron(1));
+    alice.additional_neuron_ids = Some(vec![neuron(11), neuron(12)]);
+    let council = vec![
+        alice,
+        CouncilMember::new("bob", &neuron(2)),
+        CouncilMember::new("carol", &neuron(3)),
+    ];
+    for neuron_id in [1, 11, 12, 2, 3] {
+        env.add_neuron(&neuron(neuron_id));
+    }
+    let diff: Result<CouncilDiff, CanisterError> = env.proxy_update("set_council", (council,));
+    diff.unwrap();
+    let result: Result<(), CanisterError> = env.proxy_update("set_neuron_combine_rule", (rule,));
+    result.unwrap();
+
+    let baseline = env.add_baseline_proposal();
+    env.add_proposal(&proposal(2, 3, "Upgrade the dapp"));
+    env.watch_proposals(&baseline).unwrap();
+    env.set_ballot(2, &neuron(1), 1);
+    env.set_ballot(2, &neuron(11), 2);
+    env.set_ballot(2, &neuron(12), 2);
+    env.set_ballot(2, &neuron(2), 1);
+    env.advance_time(until_evaluation());
+    env
+}
+
+#[test]
+fn combines_the_neurons_of_a_member_into_one_vote() {
+    let env = vote_with_several_neurons(NeuronCombineRule::AnyYes);
+    assert_eq!(env.registered_votes()[0].vote, Vote::Yes as i32);
+    let evaluation = env.proposal_history()[0].evaluation.clone().unwrap();
+    assert_eq!(
+        (
+            evaluation.eligible_members,
+            evaluation.voted,
+            evaluation.yes
+        ),
+        (3, 2, 2)
+    );
+
+    let env = vote_with_several_neurons(NeuronCombineRule::MajorityOfNeurons);
+    assert_eq!(env.registered_votes()[0].vote, Vote::No as i32);
+    let evaluation = env.proposal_history()[0].evaluation.clone().unwrap();
+    assert_eq!(
+        (
+            evaluation.eligible_members,
+            evaluation.voted,
+            evaluation.yes
+        ),
+        (3, 2, 1)
+    );
+}
@@ -0,0 +1,44 @@
+use ic_canister::{generate_idl, query, update, Canister, Idl, PreUpdate};
+use ic_exports::candid::{Nat, Principal};
+use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
+
+use crate::state::{TRANSFERS, TRANSFER_ERROR};
+
+/// Minimal ICRC-1 ledger used by the integration tests.
+/// Transfers always succeed unless an error has been scripted with `set_transfer_error`.
+#[derive(Canister)]
+pub struct MockLedger {
+    #[id]
+    id: Principal,
+}
+
+impl PreUpdate for MockLedger {}
+
+impl MockLedger {
+    #[update]
+    pub fn icrc1_transfer(&self, transfer: TransferArg) -> Result<Nat, TransferError> {
+        if let Some(err) = TRANSFER_ERROR.with(|error| error.borrow().clone()) {
+            return Err(err);
+        }
+
+        TRANSFERS.with(|transfers| {
+            let mut transfers = transfers.borrow_mut();
+            transfers.push(transfer);
+            Ok(Nat::from(transfers.len() as u64 - 1))
+        })
+    }
+
+    #[update]
+    pub fn set_transfer_error(&self, error: Option<TransferError>) {
+        TRANSFER_ERROR.with(|transfer_error| *transfer_error.borrow_mut() = error);
+    }
+
+    #[query]
+    pub fn get_transfers(&self) -> Vec<TransferArg> {
+        TRANSFERS.with(|transfers| transfers.borrow().clone())
+    }
+
+    pub fn idl() -> Idl {
+        generate_idl!()
+    }
+}
@@ -0,0 +1,14 @@
+mod canister;
+mod state;
+
+use crate::canister::MockLedger;
+
+fn main() {
+    let canister_e_idl = MockLedger::idl();
+    let idl = ic_exports::candid::pretty::candid::compile(
+        &canister_e_idl.env.env,
+        &Some(canister_e_idl.actor),
+    );
+
+    println!("{}", idl);
+}
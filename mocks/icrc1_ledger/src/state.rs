@@ -0,0 +1,10 @@
+use std::cell::RefCell;
+
+use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
+
+thread_local! {
+    /// Every successful transfer, in order. The index of a transfer is its block index.
+    pub static TRANSFERS: RefCell<Vec<TransferArg>> = RefCell::new(Vec::new());
+    /// If set, every transfer fails with this error.
+    pub static TRANSFER_ERROR: RefCell<Option<TransferError>> = RefCell::new(None);
+}
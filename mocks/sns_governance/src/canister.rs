@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+
+use ic_canister::{generate_idl, query, update, Canister, Idl, PreUpdate};
+use ic_exports::{candid::Principal, ic_cdk::caller};
+use ic_nervous_system_common::ledger;
+use ic_sns_governance::pb::v1::{
+    get_neuron_response, get_proposal_response,
+    governance_error::ErrorType,
+    manage_neuron::{self, claim_or_refresh::By, ClaimOrRefresh, RegisterVote},
+    manage_neuron_response::{
+        self, ClaimOrRefreshResponse, ConfigureResponse, RegisterVoteResponse,
+    },
+    Ballot, GetNeuron, GetNeuronResponse, GetProposal, GetProposalResponse,
+    ListNervousSystemFunctionsResponse, ListProposals, ListProposalsResponse, ManageNeuron,
+    ManageNeuronResponse, NervousSystemFunction, Neuron, NeuronId, Proposal, ProposalData,
+    ProposalId, Vote,
+};
+
+use crate::{
+    state::{
+        governance_error, neuron_id_from_hex, neuron_id_to_hex, now_seconds, FUNCTIONS, NEURONS,
+        PROPOSALS, REGISTERED_VOTES,
+    },
+    types::{MockProposal, RegisteredVote},
+};
+
+/// Minimal SNS governance canister used by the integration tests.
+/// Only the subset of the API used by vp_proxy is implemented.
+#[derive(Canister)]
+pub struct MockGovernance {
+    #[id]
+    id: Principal,
+}
+
+impl PreUpdate for MockGovernance {}
+
+impl MockGovernance {
+    /// Scripts a proposal. Like SNS governance, every neuron registered at this point gets a ballot,
+    /// which is unspecified unless `proposal.ballots` sets a vote.
+    #[update]
+    pub fn add_proposal(&self, proposal: MockProposal) {
+        let now = now_seconds();
+        let mut ballots: BTreeMap<String, Ballot> = NEURONS.with(|neurons| {
+            neurons
+                .borrow()
+                .keys()
+                .map(|neuron_id| {
+                    let ballot = Ballot {
+                        vote: Vote::Unspecified as i32,
+                        voting_power: 1,
+                        cast_timestamp_seconds: 0,
+                    };
+                    (neuron_id.clone(), ballot)
+                })
+                .collect()
+        });
+        for (neuron_id, vote) in proposal.ballots {
+            let ballot = Ballot {
+                vote,
+                voting_power: 1,
+                cast_timestamp_seconds: if vote == 0 { 0 } else { now },
+            };
+            ballots.insert(neuron_id, ballot);
+        }
+
+        let proposal_data = ProposalData {
+            id: Some(ProposalId { id: proposal.id }),
+            action: proposal.action,
+            proposal: Some(Proposal {
+                title: proposal.title,
+                summary: proposal.summary,
+                url: proposal.url,
+                ..Default::default()
+            }),
+            proposal_creation_timestamp_seconds: proposal.creation_timestamp_seconds.unwrap_or(now),
+            initial_voting_period_seconds: proposal.initial_voting_period_seconds,
+            proposer: proposal.proposer.map(|proposer| NeuronId {
+                id: neuron_id_from_hex(&proposer),
+            }),
+            ballots,
+            ..Default::default()
+        };
+
+        PROPOSALS.with(|proposals| {
+            proposals.borrow_mut().insert(proposal.id, proposal_data);
+        });
+    }
+
+    #[update]
+    pub fn set_ballot(&self, proposal_id: u64, neuron_id: String, vote: i32) {
+        PROPOSALS.with(|proposals| {
+            let mut proposals = proposals.borrow_mut();
+            let proposal = proposals
+                .get_mut(&proposal_id)
+                .expect("Proposal is not scripted");
+            proposal.ballots.insert(
+                neuron_id,
+                Ballot {
+                    vote,
+                    voting_power: 1,
+                    cast_timestamp_seconds: now_seconds(),
+                },
+            );
+        });
+    }
+
+    /// Marks the proposal as no longer accepting votes.
+    #[update]
+    pub fn close_proposal(&self, proposal_id: u64) {
+        PROPOSALS.with(|proposals| {
+            let mut proposals = proposals.borrow_mut();
+            let proposal = proposals
+                .get_mut(&proposal_id)
+                .expect("Proposal is not scripted");
+            proposal.reward_event_end_timestamp_seconds = Some(now_seconds());
+        });
+    }
+
+    /// Registers a neuron, e.g. the neuron of a council member.
+    #[update]
+    pub fn add_neuron(&self, neuron_id: String) {
+        let neuron_id = NeuronId {
+            id: neuron_id_from_hex(&neuron_id),
+        };
+        NEURONS.with(|neurons| {
+            neurons.borrow_mut().insert(
+                neuron_id_to_hex(&neuron_id.id),
+                Neuron {
+                    id: Some(neuron_id),
+                    ..Default::default()
+                },
+            );
+        });
+    }
+
+    #[query]
+    pub fn get_neuron(&self, request: GetNeuron) -> GetNeuronResponse {
+        let neuron = request.neuron_id.and_then(|neuron_id| {
+            NEURONS.with(|neurons| {
+                neurons
+                    .borrow()
+                    .get(&neuron_id_to_hex(&neuron_id.id))
+                    .cloned()
+            })
+        });
+
+        let result = match neuron {
+            Some(neuron) => get_neuron_response::Result::Neuron(neuron),
+            None => get_neuron_response::Result::Error(governance_error(
+                ErrorType::NotFound,
+                "No neuron for given NeuronId.",
+            )),
+        };
+
+        GetNeuronResponse {
+            result: Some(result),
+        }
+    }
+
+    /// Registers an additional function, e.g. a generic nervous system function.
+    #[update]
+    pub fn add_nervous_system_function(&self, function: NervousSystemFunction) {
+        FUNCTIONS.with(|functions| functions.borrow_mut().push(function));
+    }
+
+    #[query]
+    pub fn list_nervous_system_functions(&self) -> ListNervousSystemFunctionsResponse {
+        ListNervousSystemFunctionsResponse {
+            functions: FUNCTIONS.with(|functions| functions.borrow().clone()),
+            reserved_ids: vec![],
+        }
+    }
+
+    #[query]
+    pub fn get_registered_votes(&self) -> Vec<RegisteredVote> {
+        REGISTERED_VOTES.with(|votes| votes.borrow().clone())
+    }
+
+    #[query]
+    pub fn list_proposals(&self, request: ListProposals) -> ListProposalsResponse {
+        let limit = request.limit.min(100) as usize;
+        let proposals = PROPOSALS.with(|proposals| {
+            proposals
+                .borrow()
+                .iter()
+                .rev()
+                .filter(|(id, _)| match request.before_proposal {
+                    Some(before) => **id < before.id,
+                    None => true,
+                })
+                .filter(|(_, proposal)| !request.exclude_type.contains(&proposal.action))
+                .take(limit)
+                .map(|(_, proposal)| proposal.clone())
+                .collect()
+        });
+
+        ListProposalsResponse {
+            proposals,
+            ..Default::default()
+        }
+    }
+
+    #[query]
+    pub fn get_proposal(&self, request: GetProposal) -> GetProposalResponse {
+        let proposal = request.proposal_id.and_then(|proposal_id| {
+            PROPOSALS.with(|proposals| proposals.borrow().get(&proposal_id.id).cloned())
+        });
+
+        let result = match proposal {
+            Some(proposal) => get_proposal_response::Result::Proposal(proposal),
+            None => get_proposal_response::Result::Error(governance_error(
+                ErrorType::NotFound,
+                "No proposal for given ProposalId.",
+            )),
+        };
+
+        GetProposalResponse {
+            result: Some(result),
+        }
+    }
+
+    #[update]
+    pub fn manage_neuron(&self, request: ManageNeuron) -> ManageNeuronResponse {
+        let command = match request.command {
+            Some(manage_neuron::Command::ClaimOrRefresh(claim)) => claim_or_refresh(claim),
+            Some(manage_neuron::Command::Configure(_)) => {
+                match neuron_exists(&request.subaccount) {
+                    true => manage_neuron_response::Command::Configure(ConfigureResponse {}),
+                    false => neuron_not_found(),
+                }
+            }
+            Some(manage_neuron::Command::RegisterVote(register_vote)) => {
+                register_vote_command(&request.subaccount, register_vote)
+            }
+            _ => manage_neuron_response::Command::Error(governance_error(
+                ErrorType::InvalidCommand,
+                "Command is not supported by the mock governance canister.",
+            )),
+        };
+
+        ManageNeuronResponse {
+            command: Some(command),
+        }
+    }
+
+    pub fn idl() -> Idl {
+        generate_idl!()
+    }
+}
+
+fn neuron_exists(neuron_id: &[u8]) -> bool {
+    NEURONS.with(|neurons| neurons.borrow().contains_key(&neuron_id_to_hex(neuron_id)))
+}
+
+fn neuron_not_found() -> manage_neuron_response::Command {
+    manage_neuron_response::Command::Error(governance_error(
+        ErrorType::NotFound,
+        "Neuron not found.",
+    ))
+}
+
+fn claim_or_refresh(claim: ClaimOrRefresh) -> manage_neuron_response::Command {
+    let Some(By::MemoAndController(memo_and_controller)) = claim.by else {
+        return manage_neuron_response::Command::Error(governance_error(
+            ErrorType::InvalidCommand,
+            "Only MemoAndController claims are supported.",
+        ));
+    };
+
+    let controller = memo_and_controller
+        .controller
+        .unwrap_or_else(|| caller().into());
+    let subaccount =
+        ledger::compute_neuron_staking_subaccount(controller, memo_and_controller.memo);
+    let neuron_id = NeuronId {
+        id: subaccount.to_vec(),
+    };
+
+    NEURONS.with(|neurons| {
+        neurons.borrow_mut().insert(
+            neuron_id_to_hex(&neuron_id.id),
+            Neuron {
+                id: Some(neuron_id.clone()),
+                ..Default::default()
+            },
+        );
+    });
+
+    manage_neuron_response::Command::ClaimOrRefresh(ClaimOrRefreshResponse {
+        refreshed_neuron_id: Some(neuron_id),
+    })
+}
+
+fn register_vote_command(
+    neuron_id: &[u8],
+    register_vote: RegisterVote,
+) -> manage_neuron_response::Command {
+    if !neuron_exists(neuron_id) {
+        return neuron_not_found();
+    }
+
+    if register_vote.vote != Vote::Yes as i32 && register_vote.vote != Vote::No as i32 {
+        return manage_neuron_response::Command::Error(governance_error(
+            ErrorType::InvalidCommand,
+            "Invalid vote specified.",
+        ));
+    }
+
+    let Some(proposal_id) = register_vote.proposal else {
+        return manage_neuron_response::Command::Error(governance_error(
+            ErrorType::InvalidCommand,
+            "Proposal id is missing.",
+        ));
+    };
+
+    let neuron_hex = neuron_id_to_hex(neuron_id);
+    let now = now_seconds();
+
+    let registered = PROPOSALS.with(|proposals| {
+        let mut proposals = proposals.borrow_mut();
+        let Some(proposal) = proposals.get_mut(&proposal_id.id) else {
+            return Err(governance_error(
+                ErrorType::NotFound,
+                "No proposal for given ProposalId.",
+            ));
+        };
+        if proposal.reward_event_end_timestamp_seconds.is_some() {
+            return Err(governance_error(
+                ErrorType::PreconditionFailed,
+                "Proposal is no longer accepting votes.",
+            ));
+        }
+        proposal.ballots.insert(
+            neuron_hex.clone(),
+            Ballot {
+                vote: register_vote.vote,
+                voting_power: 1,
+                cast_timestamp_seconds: now,
+            },
+        );
+        Ok(())
+    });
+
+    match registered {
+        Ok(()) => {
+            REGISTERED_VOTES.with(|votes| {
+                votes.borrow_mut().push(RegisteredVote {
+                    proposal_id: proposal_id.id,
+                    neuron_id: neuron_hex,
+                    vote: register_vote.vote,
+                    timestamp_seconds: now,
+                })
+            });
+            manage_neuron_response::Command::RegisterVote(RegisterVoteResponse {})
+        }
+        Err(err) => manage_neuron_response::Command::Error(err),
+    }
+}
@@ -0,0 +1,15 @@
+mod canister;
+mod state;
+mod types;
+
+use crate::canister::MockGovernance;
+
+fn main() {
+    let canister_e_idl = MockGovernance::idl();
+    let idl = ic_exports::candid::pretty::candid::compile(
+        &canister_e_idl.env.env,
+        &Some(canister_e_idl.actor),
+    );
+
+    println!("{}", idl);
+}
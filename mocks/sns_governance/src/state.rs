@@ -0,0 +1,66 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use ic_exports::ic_cdk::api::time;
+use ic_sns_governance::pb::v1::{
+    governance_error::ErrorType, nervous_system_function::FunctionType, Empty, GovernanceError,
+    NervousSystemFunction, Neuron, ProposalData,
+};
+
+use crate::types::RegisteredVote;
+
+thread_local! {
+    /// Scripted proposals, keyed by proposal id.
+    pub static PROPOSALS: RefCell<BTreeMap<u64, ProposalData>> = RefCell::new(BTreeMap::new());
+    /// Known neurons, keyed by their hex encoded id.
+    pub static NEURONS: RefCell<BTreeMap<String, Neuron>> = RefCell::new(BTreeMap::new());
+    /// Every vote registered through `manage_neuron`, in the order it was received.
+    pub static REGISTERED_VOTES: RefCell<Vec<RegisteredVote>> = RefCell::new(Vec::new());
+    /// Nervous system functions returned by `list_nervous_system_functions`.
+    pub static FUNCTIONS: RefCell<Vec<NervousSystemFunction>> = RefCell::new(native_functions());
+}
+
+/// A subset of the native functions every SNS has.
+fn native_functions() -> Vec<NervousSystemFunction> {
+    [
+        (1, "Motion"),
+        (2, "Manage nervous system parameters"),
+        (3, "Upgrade SNS controlled canister"),
+        (4, "Add nervous system function"),
+        (5, "Remove nervous system function"),
+        (6, "Execute nervous system function"),
+        (7, "Upgrade SNS to next version"),
+        (8, "Manage SNS metadata"),
+        (9, "Transfer SNS treasury funds"),
+    ]
+    .into_iter()
+    .map(|(id, name)| NervousSystemFunction {
+        id,
+        name: name.to_string(),
+        description: None,
+        function_type: Some(FunctionType::NativeNervousSystemFunction(Empty {})),
+    })
+    .collect()
+}
+
+pub fn now_seconds() -> u64 {
+    time() / 1_000_000_000
+}
+
+/// Encodes a neuron id the same way SNS governance keys its ballots.
+pub fn neuron_id_to_hex(id: &[u8]) -> String {
+    id.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn neuron_id_from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).expect("Invalid neuron id"))
+        .collect()
+}
+
+pub fn governance_error(error_type: ErrorType, message: &str) -> GovernanceError {
+    GovernanceError {
+        error_type: error_type as i32,
+        error_message: message.to_string(),
+    }
+}
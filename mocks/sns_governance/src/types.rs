@@ -0,0 +1,28 @@
+use ic_exports::candid::CandidType;
+use serde::Deserialize;
+
+/// A proposal as scripted by a test.
+#[derive(CandidType, Deserialize, Clone)]
+pub struct MockProposal {
+    pub id: u64,
+    pub action: u64,
+    pub title: String,
+    pub summary: String,
+    pub url: String,
+    /// Defaults to the current time if not set.
+    pub creation_timestamp_seconds: Option<u64>,
+    pub initial_voting_period_seconds: u64,
+    /// Hex encoded ID of the neuron that submitted the proposal.
+    pub proposer: Option<String>,
+    /// Eligible neurons (hex encoded ids) and their votes. 0 means the neuron has not voted yet.
+    pub ballots: Vec<(String, i32)>,
+}
+
+/// A vote the mock received through `manage_neuron`.
+#[derive(CandidType, Deserialize, Clone)]
+pub struct RegisteredVote {
+    pub proposal_id: u64,
+    pub neuron_id: String,
+    pub vote: i32,
+    pub timestamp_seconds: u64,
+}
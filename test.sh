@@ -0,0 +1,7 @@
+#!/bin/bash
+# Builds the proxy and the mock canisters, then runs the PocketIC integration tests.
+# Requires the PocketIC server binary, pointed to by POCKET_IC_BIN.
+set -e
+cargo build --release --target wasm32-unknown-unknown --features export-api \
+    -p vp_proxy -p mock_sns_governance -p mock_icrc1_ledger
+cargo test -p integration_tests
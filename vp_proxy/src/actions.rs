@@ -0,0 +1,138 @@
+use ic_sns_governance::pb::v1::{nervous_system_function::FunctionType, NervousSystemFunction};
+
+use crate::{
+    proposals::purge_excluded_proposals,
+    state::{
+        get_allowed_action_types, get_exclusion_list, get_exclusion_mode, get_flagged_action_types,
+        get_registered_functions, ALLOWED_ACTION_IDS, EXCLUDED_ACTION_IDS, EXCLUSION_MODE,
+        FLAGGED_ACTION_HANDLING, FLAGGED_ACTION_IDS, REGISTERED_FUNCTIONS,
+    },
+    types::{ActionType, CanisterError, ExclusionMode, FlaggedActionHandling, RegisteredFunction},
+    utils::list_nervous_system_functions,
+};
+
+impl From<NervousSystemFunction> for RegisteredFunction {
+    fn from(value: NervousSystemFunction) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            is_generic: matches!(
+                value.function_type,
+                Some(FunctionType::GenericNervousSystemFunction(_))
+            ),
+        }
+    }
+}
+
+/// Fetches the functions registered in the SNS and caches them.
+/// Generic functions that were not registered at the previous fetch are flagged for review.
+/// Nothing is flagged on the first fetch, since every function is new at that point.
+pub async fn refresh_registered_functions() -> Result<Vec<RegisteredFunction>, CanisterError> {
+    let functions: Vec<RegisteredFunction> = list_nervous_system_functions()
+        .await?
+        .into_iter()
+        .map(RegisteredFunction::from)
+        .collect();
+
+    let known_functions = get_registered_functions();
+    if !known_functions.is_empty() {
+        FLAGGED_ACTION_IDS.with(|flagged| {
+            let mut flagged = flagged.borrow_mut();
+            functions
+                .iter()
+                .filter(|function| function.is_generic)
+                .filter(|function| !known_functions.iter().any(|known| known.id == function.id))
+                .for_each(|function| {
+                    if !flagged.contains(&function.id) {
+                        flagged.push(function.id);
+                    }
+                });
+        });
+    }
+
+    REGISTERED_FUNCTIONS.with(|registered| *registered.borrow_mut() = functions.clone());
+    Ok(functions)
+}
+
+/// Checks that `action_type` is a registered function and not in `list` yet.
+pub fn validate_new_action_type(
+    action_type: u64,
+    functions: &[RegisteredFunction],
+    list: &[u64],
+) -> Result<(), CanisterError> {
+    if !functions.iter().any(|function| function.id == action_type) {
+        return Err(CanisterError::UnknownActionType(action_type));
+    }
+    if list.contains(&action_type) {
+        return Err(CanisterError::ActionTypeAlreadyListed(action_type));
+    }
+    Ok(())
+}
+
+/// Marks a flagged action as reviewed.
+pub fn unflag_action_type(action_type: u64) {
+    FLAGGED_ACTION_IDS.with(|flagged| flagged.borrow_mut().retain(|action| action != &action_type));
+}
+
+/// Adds the cached function name and description to each action ID.
+pub fn describe_action_types(action_types: Vec<u64>) -> Vec<ActionType> {
+    let functions = get_registered_functions();
+    let flagged = get_flagged_action_types();
+    action_types
+        .into_iter()
+        .map(|id| {
+            let function = functions.iter().find(|function| function.id == id);
+            ActionType {
+                id,
+                name: function.map(|function| function.name.clone()),
+                description: function.and_then(|function| function.description.clone()),
+                is_generic: function.is_some_and(|function| function.is_generic),
+                flagged: flagged.contains(&id),
+            }
+        })
+        .collect()
+}
+
+/// Permits proposals with this action under the current exclusion mode.
+pub async fn allow_action_type(action_type: u64) -> Result<(), CanisterError> {
+    match get_exclusion_mode() {
+        ExclusionMode::Denylist => EXCLUDED_ACTION_IDS
+            .with(|actions| actions.borrow_mut().retain(|action| action != &action_type)),
+        ExclusionMode::Allowlist => {
+            let functions = refresh_registered_functions().await?;
+            validate_new_action_type(action_type, &functions, &get_allowed_action_types())?;
+            ALLOWED_ACTION_IDS.with(|actions| actions.borrow_mut().push(action_type));
+        }
+    }
+    unflag_action_type(action_type);
+    Ok(())
+}
+
+/// Stops handling proposals with this action under the current exclusion mode and drops the watched ones.
+pub async fn disallow_action_type(action_type: u64) -> Result<(), CanisterError> {
+    match get_exclusion_mode() {
+        ExclusionMode::Denylist => {
+            let functions = refresh_registered_functions().await?;
+            validate_new_action_type(action_type, &functions, &get_exclusion_list())?;
+            EXCLUDED_ACTION_IDS.with(|actions| actions.borrow_mut().push(action_type));
+        }
+        ExclusionMode::Allowlist => ALLOWED_ACTION_IDS
+            .with(|actions| actions.borrow_mut().retain(|action| action != &action_type)),
+    }
+    unflag_action_type(action_type);
+    purge_excluded_proposals();
+    Ok(())
+}
+
+/// Changes how flagged actions are handled and drops watched proposals that are no longer permitted.
+pub fn set_flagged_action_handling(handling: FlaggedActionHandling) {
+    FLAGGED_ACTION_HANDLING.with(|flagged_handling| flagged_handling.set(handling));
+    purge_excluded_proposals();
+}
+
+/// Switches the exclusion mode and drops watched proposals that are no longer permitted.
+pub fn set_exclusion_mode(mode: ExclusionMode) {
+    EXCLUSION_MODE.with(|exclusion_mode| exclusion_mode.set(mode));
+    purge_excluded_proposals();
+}
@@ -0,0 +1,151 @@
+use ic_exports::{
+    candid::Principal,
+    ic_cdk::{api::time, print},
+};
+
+use crate::{
+    state::{
+        get_appointed_council_members, get_inactivity_rule, INACTIVITY_RULE, MEMBER_ACTIVITY,
+        SUSPENSION_LOG,
+    },
+    types::{
+        BallotChoice, CanisterError, InactivityRule, MemberActivity, MemberBallot,
+        SuspensionChange, SuspensionEvent,
+    },
+};
+
+/// Sets the inactivity rule, or stops suspending members if not set. Current suspensions are kept.
+pub fn set_inactivity_rule(rule: Option<InactivityRule>) -> Result<(), CanisterError> {
+    if let Some(rule) = rule {
+        if rule.missed_proposals == 0 {
+            return Err(CanisterError::InvalidInactivityRule(
+                "at least one missed proposal is required".to_string(),
+            ));
+        }
+    }
+    INACTIVITY_RULE.with(|inactivity_rule| inactivity_rule.set(rule));
+    Ok(())
+}
+
+/// Updates the activity of the members after a proposal was evaluated. Members who voted themselves are
+/// reinstated, and members who missed as many proposals in a row as the inactivity rule allows are suspended.
+/// Suspended members that have not voted are not part of `ballots` and keep their suspension. Ballots of
+/// members that left the appointed council or changed their primary neuron since the council snapshot are
+/// passed over.
+pub fn record_member_activity(ballots: &[MemberBallot]) {
+    let now = time() / 1_000_000_000;
+    let rule = get_inactivity_rule();
+    let members = get_appointed_council_members();
+
+    MEMBER_ACTIVITY.with(|activity| {
+        let mut activity = activity.borrow_mut();
+        for ballot in ballots {
+            if !members
+                .iter()
+                .any(|member| member.neuron_id == ballot.neuron_id)
+            {
+                continue;
+            }
+            let member = match activity
+                .iter()
+                .position(|member| member.neuron_id == ballot.neuron_id)
+            {
+                Some(position) => &mut activity[position],
+                None => {
+                    activity.push(MemberActivity {
+                        name: ballot.name.clone(),
+                        neuron_id: ballot.neuron_id.clone(),
+                        missed_in_a_row: 0,
+                        suspended_since: None,
+                    });
+                    activity.last_mut().unwrap()
+                }
+            };
+            member.name = ballot.name.clone();
+
+            // a vote filled in by the member's delegate is not the member's own participation
+            if ballot.vote != BallotChoice::NotVoted && ballot.delegate.is_none() {
+                member.missed_in_a_row = 0;
+                if member.suspended_since.take().is_some() {
+                    log_suspension_change(member, SuspensionChange::ReinstatedByVote, now);
+                }
+                continue;
+            }
+
+            member.missed_in_a_row += 1;
+            if let Some(rule) = rule {
+                if member.suspended_since.is_none()
+                    && member.missed_in_a_row >= rule.missed_proposals
+                {
+                    member.suspended_since = Some(now);
+                    log_suspension_change(
+                        member,
+                        SuspensionChange::Suspended {
+                            missed_proposals: member.missed_in_a_row,
+                        },
+                        now,
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Moves the activity of the member whose primary neuron changed from `from` to `to`, keeping a suspension.
+pub fn rename_member_activity(from: &str, to: &str) {
+    MEMBER_ACTIVITY.with(|activity| {
+        let mut activity = activity.borrow_mut();
+        if from != to {
+            activity.retain(|member| member.neuron_id != to);
+        }
+        if let Some(member) = activity.iter_mut().find(|member| member.neuron_id == from) {
+            member.neuron_id = to.to_string();
+        }
+    });
+}
+
+/// Drops the activity of members that are no longer on the appointed council.
+pub fn remove_stale_member_activity() {
+    let members = get_appointed_council_members();
+    MEMBER_ACTIVITY.with(|activity| {
+        activity.borrow_mut().retain(|entry| {
+            members
+                .iter()
+                .any(|member| member.neuron_id == entry.neuron_id)
+        })
+    });
+}
+
+/// Lifts the suspension of the member with the given primary neuron, on behalf of `by`.
+pub fn reinstate_member(neuron_id: &str, by: Principal) -> Result<(), CanisterError> {
+    MEMBER_ACTIVITY.with(|activity| {
+        let mut activity = activity.borrow_mut();
+        let suspended = activity
+            .iter_mut()
+            .find(|member| member.neuron_id == neuron_id && member.suspended_since.is_some())
+            .ok_or(CanisterError::MemberNotSuspended(neuron_id.to_string()))?;
+        suspended.suspended_since = None;
+        suspended.missed_in_a_row = 0;
+        log_suspension_change(
+            suspended,
+            SuspensionChange::ReinstatedBy(by),
+            time() / 1_000_000_000,
+        );
+        Ok(())
+    })
+}
+
+fn log_suspension_change(member: &MemberActivity, change: SuspensionChange, now: u64) {
+    print(format!(
+        "Council member {} ({}): {:?}",
+        member.name, member.neuron_id, change
+    ));
+    SUSPENSION_LOG.with(|log| {
+        log.borrow_mut().push(SuspensionEvent {
+            name: member.name.clone(),
+            neuron_id: member.neuron_id.clone(),
+            change,
+            at_seconds: now,
+        })
+    });
+}
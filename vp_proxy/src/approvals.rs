@@ -0,0 +1,176 @@
+use ic_exports::{candid::Principal, ic_cdk::api::time};
+
+use crate::{
+    council::emergency_reset,
+    neuron::{create_neuron, increase_dissolve_delay},
+    proposals::stop_watching,
+    state::{
+        get_multisig_policy, GOVERNANCE_CANISTER_ID, MULTISIG_POLICY, NEXT_PENDING_ACTION_ID,
+        PENDING_ACTIONS,
+    },
+    types::{ApprovalOutcome, CanisterError, MultisigPolicy, PendingAction, SensitiveAction},
+    utils::not_anonymous,
+};
+
+/// Rejects direct calls to sensitive operations once a multisig policy is set.
+/// They must be submitted with `submit_action` instead.
+pub fn ensure_no_approval_required() -> Result<(), CanisterError> {
+    if get_multisig_policy().is_some() {
+        return Err(CanisterError::ApprovalRequired);
+    }
+    Ok(())
+}
+
+/// Signers must be unique and not anonymous, and the threshold must be reachable.
+pub fn validate_multisig_policy(policy: &MultisigPolicy) -> Result<(), CanisterError> {
+    let invalid = |reason: &str| -> Result<(), CanisterError> {
+        Err(CanisterError::InvalidMultisigPolicy(reason.to_string()))
+    };
+    if policy
+        .signers
+        .iter()
+        .any(|signer| not_anonymous(signer).is_err())
+    {
+        return invalid("signers cannot be anonymous");
+    }
+    if policy
+        .signers
+        .iter()
+        .enumerate()
+        .any(|(index, signer)| policy.signers[..index].contains(signer))
+    {
+        return invalid("signers must be unique");
+    }
+    if policy.threshold == 0 || usize::from(policy.threshold) > policy.signers.len() {
+        return invalid("threshold must be between 1 and the number of signers");
+    }
+    if policy.expiry_seconds == 0 {
+        return invalid("expiry must be positive");
+    }
+    Ok(())
+}
+
+/// Sets or removes the multisig policy. Pending actions are dropped, since they were approved under the previous policy.
+pub fn set_multisig_policy(policy: Option<MultisigPolicy>) {
+    MULTISIG_POLICY.with(|current| *current.borrow_mut() = policy);
+    PENDING_ACTIONS.with(|actions| actions.borrow_mut().clear());
+}
+
+/// Queues a sensitive operation with the submitter's approval. It executes right away if the threshold is 1.
+pub async fn submit_action(
+    caller: Principal,
+    action: SensitiveAction,
+) -> Result<ApprovalOutcome, CanisterError> {
+    let policy = only_signer(caller)?;
+    if let SensitiveAction::SetMultisigPolicy(Some(policy)) = &action {
+        validate_multisig_policy(policy)?;
+    }
+    remove_expired_actions();
+
+    let now = time() / 1_000_000_000;
+    let id = NEXT_PENDING_ACTION_ID.with(|id| id.replace(id.get() + 1));
+    PENDING_ACTIONS.with(|actions| {
+        actions.borrow_mut().push(PendingAction {
+            id,
+            action,
+            submitted_by: caller,
+            submitted_at_seconds: now,
+            expires_at_seconds: now.saturating_add(policy.expiry_seconds),
+            approvals: vec![caller],
+        })
+    });
+    execute_if_approved(id, &policy).await
+}
+
+/// Adds the caller's approval to a pending operation and executes it once the threshold is met.
+pub async fn approve_action(caller: Principal, id: u64) -> Result<ApprovalOutcome, CanisterError> {
+    let policy = only_signer(caller)?;
+    remove_expired_actions();
+
+    PENDING_ACTIONS.with(|actions| {
+        let mut actions = actions.borrow_mut();
+        let action = actions
+            .iter_mut()
+            .find(|action| action.id == id)
+            .ok_or(CanisterError::PendingActionNotFound(id))?;
+        if action.approvals.contains(&caller) {
+            return Err(CanisterError::AlreadyApproved(id));
+        }
+        action.approvals.push(caller);
+        Ok(())
+    })?;
+    execute_if_approved(id, &policy).await
+}
+
+/// Returns the pending operations that have not expired.
+pub fn get_active_pending_actions() -> Vec<PendingAction> {
+    let now = time() / 1_000_000_000;
+    PENDING_ACTIONS.with(|actions| {
+        actions
+            .borrow()
+            .iter()
+            .filter(|action| now < action.expires_at_seconds)
+            .cloned()
+            .collect()
+    })
+}
+
+fn only_signer(caller: Principal) -> Result<MultisigPolicy, CanisterError> {
+    match get_multisig_policy() {
+        Some(policy) if policy.signers.contains(&caller) => Ok(policy),
+        Some(_) => Err(CanisterError::Unauthorized),
+        None => Err(CanisterError::ConfigurationError),
+    }
+}
+
+fn remove_expired_actions() {
+    let now = time() / 1_000_000_000;
+    PENDING_ACTIONS.with(|actions| {
+        actions
+            .borrow_mut()
+            .retain(|action| now < action.expires_at_seconds)
+    });
+}
+
+/// Executes the operation if it has enough approvals. It leaves the queue before executing,
+/// so it runs at most once; a failed operation must be submitted again.
+async fn execute_if_approved(
+    id: u64,
+    policy: &MultisigPolicy,
+) -> Result<ApprovalOutcome, CanisterError> {
+    let approvals = PENDING_ACTIONS.with(|actions| {
+        actions
+            .borrow()
+            .iter()
+            .find(|action| action.id == id)
+            .map(|action| action.approvals.len() as u64)
+            .ok_or(CanisterError::PendingActionNotFound(id))
+    })?;
+    if approvals < u64::from(policy.threshold) {
+        return Ok(ApprovalOutcome::Pending { id, approvals });
+    }
+
+    let action = PENDING_ACTIONS.with(|actions| {
+        let mut actions = actions.borrow_mut();
+        let index = actions.iter().position(|action| action.id == id);
+        actions.remove(index.expect("the approved action is pending"))
+    });
+    execute_action(action.action).await?;
+    Ok(ApprovalOutcome::Executed { id })
+}
+
+async fn execute_action(action: SensitiveAction) -> Result<(), CanisterError> {
+    match action {
+        SensitiveAction::EmergencyReset => emergency_reset(),
+        SensitiveAction::SetGovernanceId(canister_id) => {
+            GOVERNANCE_CANISTER_ID.with(|id| *id.borrow_mut() = canister_id);
+        }
+        SensitiveAction::StopTimers => stop_watching()?,
+        SensitiveAction::CreateNeuron { amount, nonce } => {
+            create_neuron(amount, nonce).await?;
+        }
+        SensitiveAction::IncreaseDissolveDelay(delay) => increase_dissolve_delay(delay).await?,
+        SensitiveAction::SetMultisigPolicy(policy) => set_multisig_policy(policy),
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::{fmt::Debug, future::Future};
+
+use ic_exports::ic_cdk::{api::time, caller};
+
+use crate::{
+    state::AUDIT_LOG,
+    types::{AuditEntry, AuditLogPage, CanisterError},
+};
+
+/// Maximum number of entries returned by a single `get_audit_log` call.
+pub const MAX_AUDIT_LOG_PAGE_SIZE: u64 = 100;
+
+/// Runs a mutating endpoint and records the call in the audit log.
+pub fn audited<T: Debug>(
+    endpoint: &str,
+    arguments: String,
+    call: impl FnOnce() -> Result<T, CanisterError>,
+) -> Result<T, CanisterError> {
+    let called_at_seconds = time() / 1_000_000_000;
+    let result = call();
+    record_call(endpoint, arguments, called_at_seconds, &result);
+    result
+}
+
+/// Runs an asynchronous mutating endpoint and records the call in the audit log once it completes.
+pub async fn audited_async<T: Debug>(
+    endpoint: &str,
+    arguments: String,
+    call: impl Future<Output = Result<T, CanisterError>>,
+) -> Result<T, CanisterError> {
+    let called_at_seconds = time() / 1_000_000_000;
+    let result = call.await;
+    record_call(endpoint, arguments, called_at_seconds, &result);
+    result
+}
+
+/// Calls rejected as unauthorized are not recorded, so that anyone cannot fill the log.
+fn record_call<T: Debug>(
+    endpoint: &str,
+    arguments: String,
+    called_at_seconds: u64,
+    result: &Result<T, CanisterError>,
+) {
+    if matches!(result, Err(CanisterError::Unauthorized)) {
+        return;
+    }
+    AUDIT_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let id = log.len() as u64;
+        log.push(AuditEntry {
+            id,
+            caller: caller(),
+            called_at_seconds,
+            endpoint: endpoint.to_string(),
+            arguments,
+            result: format!("{:?}", result),
+        });
+    });
+}
+
+/// Returns up to `limit` entries starting at `offset`, oldest first.
+pub fn get_audit_log_page(offset: u64, limit: u64) -> AuditLogPage {
+    let limit = limit.min(MAX_AUDIT_LOG_PAGE_SIZE) as usize;
+    AUDIT_LOG.with(|log| {
+        let log = log.borrow();
+        AuditLogPage {
+            entries: log
+                .iter()
+                .skip(offset.try_into().unwrap_or(usize::MAX))
+                .take(limit)
+                .cloned()
+                .collect(),
+            total: log.len() as u64,
+        }
+    })
+}
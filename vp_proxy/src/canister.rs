@@ -5,35 +5,62 @@ use ic_canister::{
 };
 use ic_exports::{
     candid::{Nat, Principal},
-    ic_cdk::{call, caller, id, print, spawn, storage},
-    ic_cdk_timers::{clear_timer, set_timer, set_timer_interval},
-};
-use ic_nervous_system_common::ledger;
-use ic_sns_governance::pb::v1::{
-    manage_neuron::{
-        self,
-        claim_or_refresh::{By, MemoAndController},
-        configure::Operation,
-        ClaimOrRefresh, Configure, IncreaseDissolveDelay,
-    },
-    ManageNeuron, ManageNeuronResponse, NeuronId, ProposalId,
-};
-use icrc_ledger_types::icrc1::{
-    account::Account,
-    transfer::{Memo, TransferArg, TransferError},
+    ic_cdk::{api::time, caller, print, spawn, storage},
+    ic_cdk_timers::{set_timer, set_timer_interval},
 };
+use ic_sns_governance::pb::v1::{NeuronId, ProposalId};
 
 use crate::{
-    proposals::check_proposals,
+    actions::{self, describe_action_types, refresh_registered_functions, unflag_action_type},
+    activity::{self, reinstate_member},
+    approvals::{
+        self, ensure_no_approval_required, get_active_pending_actions, validate_multisig_policy,
+    },
+    audit::{audited, audited_async, get_audit_log_page},
+    council::{
+        self, apply_council_terms, normalize_council, normalize_council_member,
+        normalize_neuron_id, normalize_restored_members, only_council_member,
+    },
+    decisions::{certify_decision_log, get_decision_log_page},
+    delegation::{self, get_delegations},
+    filters::{self, find_matching_rule},
+    mirror::{self, normalize_voting_mode},
+    neuron,
+    proposals::{self, check_proposals, get_live_tally, schedule_early_decisions, stop_watching},
     state::{
-        get_council_members, get_exclusion_list, get_fetcher_timer_id, get_governance_canister_id,
-        get_ledger_canister_id, get_max_retries, get_neuron, get_proposal_history,
-        get_proposal_watchlist, get_watch_lock, COUNCIL_MEMBERS, EXCLUDED_ACTION_IDS,
-        FETCHER_TIMER_ID, GOVERNANCE_CANISTER_ID, LAST_PROPOSAL, LEDGER_CANISTER_ID, NEURON_ID,
-        PROPOSAL_HISTORY, WATCHING_PROPOSALS, WATCH_LOCK,
+        default_filter_rules, default_voting_policies, get_allowed_action_types,
+        get_appointed_council_members, get_audit_log, get_council_epochs, get_council_members,
+        get_council_source, get_decision_log, get_early_decision_interval, get_exclusion_list,
+        get_exclusion_mode, get_filter_rules, get_flagged_action_handling,
+        get_flagged_action_types, get_governance_canister_id, get_inactivity_rule,
+        get_ledger_canister_id, get_max_retries, get_member_activity, get_multisig_policy,
+        get_neuron, get_neuron_combine_rule, get_pending_actions, get_proposal_history,
+        get_proposal_watchlist, get_queued_changes, get_registered_functions, get_role_assignments,
+        get_roles, get_skipped_proposals, get_suspension_log, get_timelock_delay,
+        get_upcoming_council_members, get_voting_mode, get_voting_policies, get_watch_lock,
+        StableStateExtension, ALLOWED_ACTION_IDS, AUDIT_LOG, COUNCIL_EPOCHS, COUNCIL_MEMBERS,
+        COUNCIL_SOURCE, DECISION_LOG, DELEGATIONS, EARLY_DECISION_INTERVAL_SECONDS,
+        EXCLUDED_ACTION_IDS, EXCLUSION_MODE, FILTER_RULES, FLAGGED_ACTION_HANDLING,
+        FLAGGED_ACTION_IDS, GOVERNANCE_CANISTER_ID, INACTIVITY_RULE, LAST_PROPOSAL,
+        LEDGER_CANISTER_ID, MEMBER_ACTIVITY, MULTISIG_POLICY, NEURON_COMBINE_RULE, NEURON_ID,
+        NEXT_FILTER_RULE_ID, NEXT_PENDING_ACTION_ID, NEXT_QUEUED_CHANGE_ID, PENDING_ACTIONS,
+        PROPOSAL_HISTORY, QUEUED_CHANGES, REGISTERED_FUNCTIONS, ROLE_ASSIGNMENTS,
+        SKIPPED_PROPOSALS, SUSPENSION_LOG, TIMELOCK_DELAY_SECONDS, UPCOMING_COUNCIL_MEMBERS,
+        VETO_RIGHTS, VOTING_MODE, VOTING_POLICIES, WATCH_LOCK,
     },
-    types::{CanisterError, CouncilMember, ParticipationStatus, ProxyProposalQuery},
-    utils::{handle_intercanister_call, only_controller},
+    stats::get_council_stats,
+    timelock::{self, ensure_no_timelock, schedule_queued_changes},
+    types::{
+        ActionType, ApprovalOutcome, AuditLogPage, CanisterError, ConfigChange, CouncilDiff,
+        CouncilEpoch, CouncilMember, CouncilSource, DecisionLogPage, Delegation, ExclusionMode,
+        FilterAction, FilterMatcher, FilterRule, FilterSample, FlaggedActionHandling,
+        InactivityRule, LiveTally, MemberActivity, MemberStats, MultisigPolicy, NeuronCombineRule,
+        ParticipationStatus, PendingAction, ProxyProposalQuery, QueuedChange, Role, RoleAssignment,
+        SensitiveAction, SuspensionEvent, VetoRight, VotingMode, VotingPolicy,
+    },
+    utils::{not_anonymous, only_admin, only_roles},
+    veto::{self, get_veto_rights},
+    voting::{self, validate_voting_policy},
 };
 
 #[derive(Canister)]
@@ -47,229 +74,617 @@ impl PreUpdate for VpProxy {}
 impl VpProxy {
     #[update]
     pub fn set_governance_id(&self, canister_id: Principal) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        GOVERNANCE_CANISTER_ID.with(|id| *id.borrow_mut() = canister_id);
-        Ok(())
+        audited("set_governance_id", format!("{:?}", canister_id), || {
+            only_admin(caller())?;
+            ensure_no_approval_required()?;
+            GOVERNANCE_CANISTER_ID.with(|id| *id.borrow_mut() = canister_id);
+            Ok(())
+        })
     }
 
     #[update]
     pub fn set_ledger_id(&self, canister_id: Principal) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        LEDGER_CANISTER_ID.with(|id| *id.borrow_mut() = canister_id);
-        Ok(())
+        audited("set_ledger_id", format!("{:?}", canister_id), || {
+            only_admin(caller())?;
+            LEDGER_CANISTER_ID.with(|id| *id.borrow_mut() = canister_id);
+            Ok(())
+        })
     }
 
     #[update]
     pub async fn create_neuron(&self, amount: Nat, nonce: u64) -> Result<NeuronId, CanisterError> {
-        only_controller(caller())?;
+        audited_async("create_neuron", format!("{:?}", (&amount, &nonce)), async {
+            only_admin(caller())?;
+            ensure_no_approval_required()?;
+            neuron::create_neuron(amount, nonce).await
+        })
+        .await
+    }
 
-        if get_neuron().is_ok() {
-            return Err(CanisterError::NeuronAlreadySet);
-        }
+    #[update]
+    pub async fn increase_disolve_delay(&self, delay: u32) -> Result<(), CanisterError> {
+        audited_async("increase_disolve_delay", format!("{:?}", delay), async {
+            only_admin(caller())?;
+            ensure_no_approval_required()?;
+            neuron::increase_dissolve_delay(delay).await
+        })
+        .await
+    }
 
-        // transfers all CONF tokens to the neuron's subaccount under the governance canister id
-        let subaccount = ledger::compute_neuron_staking_subaccount(id().into(), nonce);
-        let governance_canister_id = get_governance_canister_id()?;
-        let ledger_canister_id = get_ledger_canister_id()?;
+    /// Adds a council member after confirming that its neuron exists in the SNS.
+    /// Names and neuron IDs must be unique within the council.
+    #[update]
+    pub async fn add_council_member(
+        &self,
+        name: String,
+        neuron_id: String,
+    ) -> Result<(), CanisterError> {
+        audited_async(
+            "add_council_member",
+            format!("{:?}", (&name, &neuron_id)),
+            async {
+                only_admin(caller())?;
+                ensure_no_timelock()?;
+                let member = normalize_council_member(CouncilMember {
+                    name,
+                    neuron_id,
+                    additional_neuron_ids: None,
+                    term_start: None,
+                    term_end: None,
+                    principal: None,
+                })?;
+                council::add_council_member(member).await
+            },
+        )
+        .await
+    }
 
-        let transfer_args = TransferArg {
-            from_subaccount: None,
-            to: Account {
-                owner: governance_canister_id,
-                subaccount: Some(subaccount.0),
+    /// Replaces the council member with the given primary neuron ID, e.g. to change its name or neurons.
+    #[update]
+    pub async fn update_council_member(
+        &self,
+        neuron_id: String,
+        member: CouncilMember,
+    ) -> Result<(), CanisterError> {
+        audited_async(
+            "update_council_member",
+            format!("{:?}", (&neuron_id, &member)),
+            async {
+                only_admin(caller())?;
+                ensure_no_timelock()?;
+                let neuron_id = normalize_neuron_id(&neuron_id)?;
+                let member = normalize_council_member(member)?;
+                council::update_council_member(&neuron_id, member).await
             },
-            fee: None,
-            created_at_time: None,
-            memo: Some(Memo::from(nonce)),
-            amount,
-        };
+        )
+        .await
+    }
 
-        let transfer_response = call(ledger_canister_id, "icrc1_transfer", (transfer_args,)).await;
-
-        match handle_intercanister_call::<Result<Nat, TransferError>>(transfer_response)? {
-            Err(err) => Err(CanisterError::Unknown(format!(
-                "Error occured on token transfer: {:#?}",
-                err
-            ))),
-            _ => Ok(()),
-        }?;
-
-        // claim neuron
-        let neuron_claim_args = ManageNeuron {
-            subaccount: subaccount.to_vec(),
-            command: Some(manage_neuron::Command::ClaimOrRefresh(ClaimOrRefresh {
-                by: Some(By::MemoAndController(MemoAndController {
-                    memo: nonce,
-                    controller: Some(id().into()),
-                })),
-            })),
-        };
+    /// Sets the signers that must approve sensitive operations. Once set, the policy itself
+    /// can only be changed through `submit_action`.
+    #[update]
+    pub fn set_multisig_policy(&self, policy: MultisigPolicy) -> Result<(), CanisterError> {
+        audited("set_multisig_policy", format!("{:?}", policy), || {
+            only_admin(caller())?;
+            ensure_no_approval_required()?;
+            validate_multisig_policy(&policy)?;
+            approvals::set_multisig_policy(Some(policy));
+            Ok(())
+        })
+    }
 
-        let claim_response = call(
-            governance_canister_id,
-            "manage_neuron",
-            (neuron_claim_args,),
-        )
-        .await;
-
-        let manage_neuron_response =
-            handle_intercanister_call::<ManageNeuronResponse>(claim_response)?;
-
-        if let Some(command) = manage_neuron_response.command {
-            let neuron_id = match command {
-                ic_sns_governance::pb::v1::manage_neuron_response::Command::ClaimOrRefresh(
-                    claim_or_refresh_response,
-                ) => Ok(claim_or_refresh_response.refreshed_neuron_id),
-                _ => Err(CanisterError::Unknown(
-                    "Could not handle the manage neuron response".to_string(),
-                )),
-            }?;
-
-            if let Some(neuron_id_unwrapped) = neuron_id {
-                NEURON_ID.with(|id| *id.borrow_mut() = Some(neuron_id_unwrapped.clone()));
-                return Ok(neuron_id_unwrapped);
-            }
-
-            return Err(CanisterError::Unknown(
-                "Neuron Id couldn't be generated.".to_string(),
-            ));
-        } else {
-            return Err(CanisterError::Unknown(
-                "Could not handle the manage neuron response".to_string(),
-            ));
-        }
+    /// Submits a sensitive operation for approval by the multisig signers, approving it on the caller's behalf.
+    #[update]
+    pub async fn submit_action(
+        &self,
+        action: SensitiveAction,
+    ) -> Result<ApprovalOutcome, CanisterError> {
+        audited_async("submit_action", format!("{:?}", action), async {
+            approvals::submit_action(caller(), action).await
+        })
+        .await
     }
 
+    /// Approves a pending sensitive operation. It executes once the threshold is met.
     #[update]
-    pub async fn increase_disolve_delay(&self, delay: u32) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-
-        let neuron_id = get_neuron()?;
-        let governance_canister_id = get_governance_canister_id()?;
-
-        let neuron_claim_args = ManageNeuron {
-            subaccount: neuron_id.id,
-            command: Some(manage_neuron::Command::Configure(Configure {
-                operation: Some(Operation::IncreaseDissolveDelay(IncreaseDissolveDelay {
-                    additional_dissolve_delay_seconds: delay,
-                })),
-            })),
-        };
+    pub async fn approve_action(&self, id: u64) -> Result<ApprovalOutcome, CanisterError> {
+        audited_async("approve_action", format!("{:?}", id), async {
+            approvals::approve_action(caller(), id).await
+        })
+        .await
+    }
 
-        let claim_response = call(
-            governance_canister_id,
-            "manage_neuron",
-            (neuron_claim_args,),
+    /// Sets how long security-relevant configuration changes wait before they take effect.
+    /// Once set, the delay itself can only be changed through `queue_change`.
+    #[update]
+    pub fn set_timelock_delay(&self, delay_seconds: u64) -> Result<(), CanisterError> {
+        audited("set_timelock_delay", format!("{:?}", delay_seconds), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            TIMELOCK_DELAY_SECONDS.with(|delay| delay.set(delay_seconds));
+            Ok(())
+        })
+    }
+
+    /// Queues a configuration change to take effect after the timelock delay, and returns its ID.
+    #[update]
+    pub async fn queue_change(&self, change: ConfigChange) -> Result<u64, CanisterError> {
+        audited_async("queue_change", format!("{:?}", change), async {
+            timelock::queue_change(caller(), change).await
+        })
+        .await
+    }
+
+    /// Cancels a queued configuration change before it takes effect.
+    #[update]
+    pub fn cancel_change(&self, id: u64) -> Result<(), CanisterError> {
+        audited("cancel_change", format!("{:?}", id), || {
+            timelock::cancel_change(caller(), id)
+        })
+    }
+
+    /// Grants a role to a principal.
+    #[update]
+    pub fn grant_role(&self, principal: Principal, role: Role) -> Result<(), CanisterError> {
+        audited("grant_role", format!("{:?}", (&principal, &role)), || {
+            only_admin(caller())?;
+            not_anonymous(&principal)?;
+            ROLE_ASSIGNMENTS.with(|assignments| {
+                let mut assignments = assignments.borrow_mut();
+                if !assignments
+                    .iter()
+                    .any(|assignment| assignment.principal == principal && assignment.role == role)
+                {
+                    assignments.push(RoleAssignment { principal, role });
+                }
+            });
+            Ok(())
+        })
+    }
+
+    /// Revokes a granted role. The admin role of the controllers cannot be revoked.
+    #[update]
+    pub fn revoke_role(&self, principal: Principal, role: Role) -> Result<(), CanisterError> {
+        audited("revoke_role", format!("{:?}", (&principal, &role)), || {
+            only_admin(caller())?;
+            ROLE_ASSIGNMENTS.with(|assignments| {
+                let mut assignments = assignments.borrow_mut();
+                let count = assignments.len();
+                assignments.retain(|assignment| {
+                    !(assignment.principal == principal && assignment.role == role)
+                });
+                if assignments.len() == count {
+                    return Err(CanisterError::RoleAssignmentNotFound);
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Lists the granted roles. The controllers, which are always admins, are not listed.
+    #[query]
+    pub fn get_role_assignments(&self) -> Result<Vec<RoleAssignment>, CanisterError> {
+        only_roles(caller(), &[Role::Operator, Role::Auditor])?;
+        Ok(get_role_assignments())
+    }
+
+    /// Returns a page of the decision log with a certificate of its chain tip, so that anyone can
+    /// verify that the recorded votes were not rewritten. At most 100 entries are returned at once.
+    #[query]
+    pub fn get_decision_log(&self, offset: u64, limit: u64) -> DecisionLogPage {
+        get_decision_log_page(offset, limit)
+    }
+
+    /// Returns the participation of every council member in the votes decided by the proxy, optionally
+    /// limited to the decisions taken between `from_seconds` and `to_seconds`.
+    #[query]
+    pub fn get_council_stats(
+        &self,
+        from_seconds: Option<u64>,
+        to_seconds: Option<u64>,
+    ) -> Vec<MemberStats> {
+        get_council_stats(from_seconds, to_seconds)
+    }
+
+    /// Returns a page of the audit log, which records every call to a mutating endpoint
+    /// except unauthorized ones. At most 100 entries are returned at once.
+    #[query]
+    pub fn get_audit_log(&self, offset: u64, limit: u64) -> Result<AuditLogPage, CanisterError> {
+        only_roles(caller(), &[Role::Auditor])?;
+        Ok(get_audit_log_page(offset, limit))
+    }
+
+    /// Returns the roles of the caller.
+    #[query]
+    pub fn get_my_roles(&self) -> Vec<Role> {
+        get_roles(&caller())
+    }
+
+    /// Returns the council member bound to the caller's principal.
+    #[query]
+    pub fn get_my_council_member(&self) -> Result<CouncilMember, CanisterError> {
+        only_council_member(caller())
+    }
+
+    /// Lets a council member bound to the caller's principal change their name.
+    #[update]
+    pub async fn set_my_display_name(&self, name: String) -> Result<(), CanisterError> {
+        audited_async("set_my_display_name", format!("{:?}", name), async {
+            let member = only_council_member(caller())?;
+            let neuron_id = member.neuron_id.clone();
+            let member = normalize_council_member(CouncilMember { name, ..member })?;
+            council::update_council_member(&neuron_id, member).await
+        })
+        .await
+    }
+
+    /// Lets a council member bound to the caller's principal replace their neurons.
+    #[update]
+    pub async fn set_my_neuron_ids(
+        &self,
+        neuron_id: String,
+        additional_neuron_ids: Vec<String>,
+    ) -> Result<(), CanisterError> {
+        audited_async(
+            "set_my_neuron_ids",
+            format!("{:?}", (&neuron_id, &additional_neuron_ids)),
+            async {
+                let member = only_council_member(caller())?;
+                let current_neuron_id = member.neuron_id.clone();
+                let member = normalize_council_member(CouncilMember {
+                    neuron_id,
+                    additional_neuron_ids: Some(additional_neuron_ids),
+                    ..member
+                })?;
+                council::update_council_member(&current_neuron_id, member).await
+            },
+        )
+        .await
+    }
+
+    /// Lets a council member bound to the caller's principal have `delegate` vote on their behalf between
+    /// `starts_at` and `ends_at`, replacing their previous delegation.
+    #[update]
+    pub fn set_my_delegation(
+        &self,
+        delegate: String,
+        starts_at: u64,
+        ends_at: u64,
+    ) -> Result<(), CanisterError> {
+        audited(
+            "set_my_delegation",
+            format!("{:?}", (&delegate, &starts_at, &ends_at)),
+            || {
+                let member = only_council_member(caller())?;
+                delegation::set_delegation(Delegation {
+                    delegator: member.neuron_id,
+                    delegate,
+                    starts_at,
+                    ends_at,
+                })
+            },
         )
-        .await;
-
-        let manage_neuron_response =
-            handle_intercanister_call::<ManageNeuronResponse>(claim_response)?;
-
-        if let Some(command) = manage_neuron_response.command {
-            return match command {
-                ic_sns_governance::pb::v1::manage_neuron_response::Command::Configure(_) => Ok(()),
-                _ => Err(CanisterError::Unknown(
-                    "Could not handle the manage neuron response".to_string(),
-                )),
-            };
-        }
-        return Err(CanisterError::Unknown(
-            "Could not handle the manage neuron response".to_string(),
-        ));
     }
 
+    /// Lets a council member bound to the caller's principal remove their delegation.
     #[update]
-    pub fn add_council_member(&self, name: String, neuron_id: String) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        COUNCIL_MEMBERS
-            .with(|members| members.borrow_mut().push(CouncilMember { name, neuron_id }));
-        Ok(())
+    pub fn remove_my_delegation(&self) -> Result<(), CanisterError> {
+        audited("remove_my_delegation", String::new(), || {
+            let member = only_council_member(caller())?;
+            delegation::remove_delegation(&member.neuron_id)
+        })
+    }
+
+    /// Has one council member vote on behalf of another for a while, replacing the delegator's previous
+    /// delegation. Delegations cannot be chained.
+    #[update]
+    pub fn set_delegation(&self, delegation: Delegation) -> Result<(), CanisterError> {
+        audited("set_delegation", format!("{:?}", delegation), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            delegation::set_delegation(delegation)
+        })
+    }
+
+    /// Removes the delegation of the council member with the given primary neuron.
+    #[update]
+    pub fn remove_delegation(&self, delegator: String) -> Result<(), CanisterError> {
+        audited("remove_delegation", format!("{:?}", delegator), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            let delegator = normalize_neuron_id(&delegator)?;
+            delegation::remove_delegation(&delegator)
+        })
     }
 
     #[update]
     pub fn remove_council_member(&self, neuron_id: String) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        COUNCIL_MEMBERS.with(|members| {
-            members
-                .borrow_mut()
-                .retain(|member| member.neuron_id != neuron_id)
-        });
-        Ok(())
+        audited("remove_council_member", format!("{:?}", neuron_id), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            let neuron_id = normalize_neuron_id(&neuron_id)?;
+            council::remove_council_member(&neuron_id)
+        })
+    }
+
+    /// Suspends council members that left as many evaluated proposals in a row unvoted as the rule allows.
+    /// Members are no longer suspended if not set, but current suspensions are kept.
+    #[update]
+    pub fn set_inactivity_rule(&self, rule: Option<InactivityRule>) -> Result<(), CanisterError> {
+        audited("set_inactivity_rule", format!("{:?}", rule), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            activity::set_inactivity_rule(rule)
+        })
+    }
+
+    /// Lifts the suspension of the council member with the given primary neuron.
+    #[update]
+    pub fn reinstate_council_member(&self, neuron_id: String) -> Result<(), CanisterError> {
+        audited(
+            "reinstate_council_member",
+            format!("{:?}", neuron_id),
+            || {
+                only_admin(caller())?;
+                ensure_no_timelock()?;
+                let neuron_id = normalize_neuron_id(&neuron_id)?;
+                reinstate_member(&neuron_id, caller())
+            },
+        )
+    }
+
+    /// Lets a council member veto proposals of the given action types by voting no, replacing the member's
+    /// previous veto right.
+    #[update]
+    pub fn set_veto_right(&self, right: VetoRight) -> Result<(), CanisterError> {
+        audited("set_veto_right", format!("{:?}", right), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            veto::set_veto_right(right)
+        })
+    }
+
+    /// Removes the veto right of the council member with the given primary neuron.
+    #[update]
+    pub fn remove_veto_right(&self, neuron_id: String) -> Result<(), CanisterError> {
+        audited("remove_veto_right", format!("{:?}", neuron_id), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            let neuron_id = normalize_neuron_id(&neuron_id)?;
+            veto::remove_veto_right(&neuron_id)
+        })
+    }
+
+    /// Replaces the whole council in one step, including members whose term has not started, and returns
+    /// the changes. Nothing changes if any member is invalid, duplicated or has a neuron unknown to the SNS.
+    #[update]
+    pub async fn set_council(
+        &self,
+        members: Vec<CouncilMember>,
+    ) -> Result<CouncilDiff, CanisterError> {
+        audited_async("set_council", format!("{:?}", members), async {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            let council = normalize_council(members)?;
+            council::set_council(council).await
+        })
+        .await
     }
 
     #[update]
     pub fn emergency_reset(&self) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        COUNCIL_MEMBERS.with(|members| *members.borrow_mut() = vec![]); // any timer should be cancelled?
-        Ok(())
+        audited("emergency_reset", String::new(), || {
+            only_admin(caller())?;
+            ensure_no_approval_required()?;
+            council::emergency_reset();
+            Ok(())
+        })
     }
 
+    /// Permits proposals with this action: removes it from the exclusion list in denylist mode,
+    /// or adds it to the allowed actions in allowlist mode. Only registered functions can be added.
     #[update]
-    pub fn allow_action_type(&self, action_type: u64) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        EXCLUDED_ACTION_IDS
-            .with(|actions| actions.borrow_mut().retain(|action| action != &action_type));
-        Ok(())
+    pub async fn allow_action_type(&self, action_type: u64) -> Result<(), CanisterError> {
+        audited_async("allow_action_type", format!("{:?}", action_type), async {
+            only_roles(caller(), &[Role::Operator])?;
+            ensure_no_timelock()?;
+            actions::allow_action_type(action_type).await
+        })
+        .await
     }
 
+    /// Stops handling proposals with this action: adds it to the exclusion list in denylist mode,
+    /// or removes it from the allowed actions in allowlist mode. Only registered functions can be added.
+    /// Watched proposals with this action are dropped.
     #[update]
-    pub fn disallow_action_type(&self, action_type: u64) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        EXCLUDED_ACTION_IDS.with(|actions| actions.borrow_mut().push(action_type));
-        WATCHING_PROPOSALS.with(|proposals| {
-            let mut proposals_mutable = proposals.borrow_mut();
-            proposals_mutable.iter().for_each(|proposal| {
-                if proposal.action == action_type && proposal.timer_id.is_some() {
-                    // cancel its timer
-                    clear_timer(proposal.timer_id.unwrap());
-                }
-            });
-            proposals_mutable.retain(|proposal| proposal.action != action_type);
-        });
-        Ok(())
+    pub async fn disallow_action_type(&self, action_type: u64) -> Result<(), CanisterError> {
+        audited_async(
+            "disallow_action_type",
+            format!("{:?}", action_type),
+            async {
+                only_roles(caller(), &[Role::Operator])?;
+                ensure_no_timelock()?;
+                actions::disallow_action_type(action_type).await
+            },
+        )
+        .await
     }
 
+    /// Marks a flagged action as reviewed without changing the exclusion lists.
     #[update]
-    pub fn stop_timers(&self) -> Result<(), CanisterError> {
-        only_controller(caller())?;
+    pub fn acknowledge_action_type(&self, action_type: u64) -> Result<(), CanisterError> {
+        audited(
+            "acknowledge_action_type",
+            format!("{:?}", action_type),
+            || {
+                only_roles(caller(), &[Role::Operator])?;
+                ensure_no_timelock()?;
+                unflag_action_type(action_type);
+                Ok(())
+            },
+        )
+    }
 
-        if !get_watch_lock() {
-            // lock is off.
-            return Err(CanisterError::WatchingIsAlreadyStopped);
-        }
+    /// Decides whether proposals with a flagged action are handled until the action is reviewed.
+    #[update]
+    pub fn set_flagged_action_handling(
+        &self,
+        handling: FlaggedActionHandling,
+    ) -> Result<(), CanisterError> {
+        audited(
+            "set_flagged_action_handling",
+            format!("{:?}", handling),
+            || {
+                only_roles(caller(), &[Role::Operator])?;
+                ensure_no_timelock()?;
+                actions::set_flagged_action_handling(handling);
+                Ok(())
+            },
+        )
+    }
 
-        // Cancel all timers
-        WATCHING_PROPOSALS.with(|proposals| {
-            let mut proposals = proposals.borrow_mut();
-            for proposal in proposals.iter_mut() {
-                proposal.lock = true;
-                if let Some(timer_id) = proposal.timer_id {
-                    clear_timer(timer_id);
-                }
-                proposal.lock = false;
-            }
-            *proposals = vec![];
-        });
+    /// Decides whether proposals are evaluated against the council at discovery time or the live council.
+    #[update]
+    pub fn set_council_source(&self, source: CouncilSource) -> Result<(), CanisterError> {
+        audited("set_council_source", format!("{:?}", source), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            COUNCIL_SOURCE.with(|council_source| council_source.set(source));
+            Ok(())
+        })
+    }
 
-        let fetcher_timer_id = get_fetcher_timer_id();
+    /// Decides how the ballots of a council member's neurons are combined into the member's vote.
+    #[update]
+    pub fn set_neuron_combine_rule(&self, rule: NeuronCombineRule) -> Result<(), CanisterError> {
+        audited("set_neuron_combine_rule", format!("{:?}", rule), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            NEURON_COMBINE_RULE.with(|combine_rule| combine_rule.set(rule));
+            Ok(())
+        })
+    }
 
-        if fetcher_timer_id.is_some() {
-            clear_timer(fetcher_timer_id.unwrap());
-        }
+    /// Turns on voting as soon as the council outcome of a watched proposal can no longer change, rechecking
+    /// the watched proposals every `interval_seconds`. Turns early decisions off if not set.
+    #[update]
+    pub fn set_early_decision_interval(
+        &self,
+        interval_seconds: Option<u64>,
+    ) -> Result<(), CanisterError> {
+        audited(
+            "set_early_decision_interval",
+            format!("{:?}", interval_seconds),
+            || {
+                only_admin(caller())?;
+                proposals::set_early_decision_interval(interval_seconds)
+            },
+        )
+    }
+
+    /// Fetches the functions registered in the SNS, flagging new generic functions.
+    #[update]
+    pub async fn refresh_action_types(&self) -> Result<Vec<ActionType>, CanisterError> {
+        audited_async("refresh_action_types", String::new(), async {
+            only_roles(caller(), &[Role::Operator])?;
+            let functions = refresh_registered_functions().await?;
+            Ok(describe_action_types(
+                functions.into_iter().map(|function| function.id).collect(),
+            ))
+        })
+        .await
+    }
+
+    /// Switches between the exclusion list and the allowed actions list. Each mode keeps its own list.
+    /// Watched proposals that are not permitted under the new mode are dropped.
+    #[update]
+    pub fn set_exclusion_mode(&self, mode: ExclusionMode) -> Result<(), CanisterError> {
+        audited("set_exclusion_mode", format!("{:?}", mode), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            actions::set_exclusion_mode(mode);
+            Ok(())
+        })
+    }
+
+    #[update]
+    pub fn add_filter_rule(
+        &self,
+        matcher: FilterMatcher,
+        action: FilterAction,
+    ) -> Result<u64, CanisterError> {
+        audited(
+            "add_filter_rule",
+            format!("{:?}", (&matcher, &action)),
+            || {
+                only_roles(caller(), &[Role::Operator])?;
+                ensure_no_timelock()?;
+                filters::add_filter_rule(matcher, action)
+            },
+        )
+    }
+
+    #[update]
+    pub fn remove_filter_rule(&self, id: u64) -> Result<(), CanisterError> {
+        audited("remove_filter_rule", format!("{:?}", id), || {
+            only_roles(caller(), &[Role::Operator])?;
+            ensure_no_timelock()?;
+            filters::remove_filter_rule(id)
+        })
+    }
+
+    /// Decides whether the proxy counts the council's ballots or copies the vote of the first listed neuron
+    /// that voted. Veto rights apply in both modes: in mirror mode, a council member vetoing the proposal
+    /// makes the proxy vote no whatever the mirrored neurons voted.
+    #[update]
+    pub async fn set_voting_mode(&self, mode: VotingMode) -> Result<(), CanisterError> {
+        audited_async("set_voting_mode", format!("{:?}", mode), async {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            let mode = normalize_voting_mode(mode)?;
+            mirror::set_voting_mode(mode).await
+        })
+        .await
+    }
 
-        FETCHER_TIMER_ID.with(|id| *id.borrow_mut() = None);
+    /// Adds a voting policy, or replaces the policy with the same name.
+    #[update]
+    pub fn set_voting_policy(&self, policy: VotingPolicy) -> Result<(), CanisterError> {
+        audited("set_voting_policy", format!("{:?}", policy), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            validate_voting_policy(&policy)?;
+            voting::set_voting_policy(policy);
+            Ok(())
+        })
+    }
 
-        WATCH_LOCK.with(|lock| lock.set(false));
+    #[update]
+    pub fn remove_voting_policy(&self, name: String) -> Result<(), CanisterError> {
+        audited("remove_voting_policy", format!("{:?}", name), || {
+            only_admin(caller())?;
+            ensure_no_timelock()?;
+            voting::remove_voting_policy(&name)
+        })
+    }
 
-        Ok(())
+    #[update]
+    pub fn stop_timers(&self) -> Result<(), CanisterError> {
+        audited("stop_timers", String::new(), || {
+            only_admin(caller())?;
+            ensure_no_approval_required()?;
+            stop_watching()
+        })
     }
 
     #[update]
     pub fn clear_proposal_history(&self) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        PROPOSAL_HISTORY.with(|history| *history.borrow_mut() = vec![]);
-        Ok(())
+        audited("clear_proposal_history", String::new(), || {
+            only_admin(caller())?;
+            PROPOSAL_HISTORY.with(|history| *history.borrow_mut() = vec![]);
+            SKIPPED_PROPOSALS.with(|skipped| *skipped.borrow_mut() = vec![]);
+            Ok(())
+        })
     }
 
     #[update]
@@ -279,87 +694,103 @@ impl VpProxy {
         from_proposal_action: u64,
         from_proposal_creation_timestamp: u64,
     ) -> Result<(), CanisterError> {
-        only_controller(caller())?;
-        get_neuron()?;
-        get_governance_canister_id()?;
-        get_ledger_canister_id()?;
-
-        if get_watch_lock() {
-            // lock is already turned on.
-            return Err(CanisterError::WatchingIsAlreadyInProgress);
-        }
+        audited(
+            "watch_proposals",
+            format!(
+                "{:?}",
+                (
+                    &from_proposal,
+                    &from_proposal_action,
+                    &from_proposal_creation_timestamp
+                )
+            ),
+            || {
+                only_admin(caller())?;
+                get_neuron()?;
+                get_governance_canister_id()?;
+                get_ledger_canister_id()?;
 
-        LAST_PROPOSAL.with(|proposal| {
-            *proposal.borrow_mut() = Some(ProxyProposalQuery {
-                id: from_proposal,
-                action: from_proposal_action,
-                creation_timestamp: from_proposal_creation_timestamp,
-                participation_status: ParticipationStatus::Undecided, // doesn't matter
-                timer_scheduled_for: None,
-            })
-        });
-
-        set_timer(Duration::ZERO, || {
-            spawn(async {
-                let max_retries = get_max_retries();
-                for _ in 0..max_retries {
-                    let checked_proposals = check_proposals().await;
-                    if checked_proposals.is_err() {
-                        let err = checked_proposals.err().unwrap();
-                        print(format!(
-                            "Proposals check cycle failed. Retrying. Returned error is: {:#?}",
-                            err
-                        ));
-                    } else {
-                        break;
-                    }
+                if get_watch_lock() {
+                    // lock is already turned on.
+                    return Err(CanisterError::WatchingIsAlreadyInProgress);
                 }
-            })
-        });
 
-        set_timer_interval(Duration::from_secs(86_400), || {
-            spawn(async {
-                loop {
-                    let checked_proposals = check_proposals().await;
-                    if checked_proposals.is_err() {
-                        let err = checked_proposals.err().unwrap();
-                        print(format!(
-                            "Proposals check cycle failed. Retrying. Returned error is: {:#?}",
-                            err
-                        ));
-                    } else {
-                        break;
-                    }
-                }
-            })
-        });
+                LAST_PROPOSAL.with(|proposal| {
+                    *proposal.borrow_mut() = Some(ProxyProposalQuery {
+                        id: from_proposal,
+                        action: from_proposal_action,
+                        creation_timestamp: from_proposal_creation_timestamp,
+                        participation_status: ParticipationStatus::Undecided, // doesn't matter
+                        timer_scheduled_for: None,
+                        policy: None,
+                        council_epoch: None,
+                        evaluation: None,
+                        title: None,
+                        url: None,
+                    })
+                });
+
+                set_timer(Duration::ZERO, || {
+                    spawn(async {
+                        let max_retries = get_max_retries();
+                        for _ in 0..max_retries {
+                            let checked_proposals = check_proposals().await;
+                            if checked_proposals.is_err() {
+                                let err = checked_proposals.err().unwrap();
+                                print(format!(
+                                "Proposals check cycle failed. Retrying. Returned error is: {:#?}",
+                                err
+                            ));
+                            } else {
+                                break;
+                            }
+                        }
+                    })
+                });
 
-        WATCH_LOCK.with(|lock| lock.set(true));
+                set_timer_interval(Duration::from_secs(86_400), || {
+                    spawn(async {
+                        loop {
+                            let checked_proposals = check_proposals().await;
+                            if checked_proposals.is_err() {
+                                let err = checked_proposals.err().unwrap();
+                                print(format!(
+                                "Proposals check cycle failed. Retrying. Returned error is: {:#?}",
+                                err
+                            ));
+                            } else {
+                                break;
+                            }
+                        }
+                    })
+                });
 
-        Ok(())
+                WATCH_LOCK.with(|lock| lock.set(true));
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Fetches the current ballots of a watched proposal and returns how the proxy would vote if it
+    /// was evaluated now, without voting. Open to council members and role holders, since every call
+    /// makes a call to governance.
+    #[update]
+    pub async fn get_live_tally(&self, id: ProposalId) -> Result<LiveTally, CanisterError> {
+        if only_council_member(caller()).is_err() {
+            only_roles(caller(), &[Role::Operator, Role::Auditor])?;
+        }
+        get_live_tally(id).await
     }
 
     #[query]
     pub fn get_proposal_status(&self, id: ProposalId) -> Option<ProxyProposalQuery> {
-        // check both history and watching proposals.
-        let mut proposal: Option<ProxyProposalQuery> = None;
-        PROPOSAL_HISTORY.with(|proposals| {
-            let _ = proposals.borrow().iter().map(|proposal_data| {
-                if proposal_data.id == id {
-                    proposal = Some(proposal_data.clone().into())
-                }
-            });
-        });
-
-        WATCHING_PROPOSALS.with(|proposals| {
-            let _ = proposals.borrow().iter().map(|proposal_data| {
-                if proposal_data.id == id {
-                    proposal = Some(proposal_data.clone().into())
-                }
-            });
-        });
-
-        proposal
+        // check the watchlist, the history and the skipped proposals.
+        get_proposal_watchlist()
+            .into_iter()
+            .chain(get_proposal_history())
+            .chain(get_skipped_proposals())
+            .find(|proposal| proposal.id == id)
     }
 
     #[query]
@@ -367,19 +798,170 @@ impl VpProxy {
         COUNCIL_MEMBERS.with(|members| members.borrow().clone())
     }
 
+    /// Lists the appointed members whose term has not started yet, by term start.
+    #[query]
+    pub fn get_upcoming_council_members(&self) -> Vec<CouncilMember> {
+        let mut members = get_upcoming_council_members();
+        members.sort_by_key(|member| member.term_start);
+        members
+    }
+
+    /// Lists the council members whose term ends, by term end.
+    #[query]
+    pub fn get_upcoming_expirations(&self) -> Vec<CouncilMember> {
+        let mut members: Vec<CouncilMember> = get_appointed_council_members()
+            .into_iter()
+            .filter(|member| member.term_end.is_some())
+            .collect();
+        members.sort_by_key(|member| member.term_end);
+        members
+    }
+
+    /// Lists every recorded council epoch, oldest first.
+    #[query]
+    pub fn get_council_epochs(&self) -> Vec<CouncilEpoch> {
+        get_council_epochs()
+    }
+
+    #[query]
+    pub fn get_council_epoch(&self, epoch: u64) -> Option<CouncilEpoch> {
+        get_council_epochs()
+            .into_iter()
+            .find(|council_epoch| council_epoch.epoch == epoch)
+    }
+
     #[query]
     pub fn get_proposal_history(&self) -> Vec<ProxyProposalQuery> {
         get_proposal_history()
     }
 
+    /// Lists the proposals the scanner found but did not watch, with the reason they were skipped.
+    #[query]
+    pub fn get_skipped_proposals(&self) -> Vec<ProxyProposalQuery> {
+        get_skipped_proposals()
+    }
+
     #[query]
     pub fn get_proposal_watchlist(&self) -> Vec<ProxyProposalQuery> {
         get_proposal_watchlist()
     }
 
     #[query]
-    pub fn get_exclusion_list(&self) -> Vec<u64> {
-        get_exclusion_list()
+    pub fn get_exclusion_list(&self) -> Vec<ActionType> {
+        describe_action_types(get_exclusion_list())
+    }
+
+    #[query]
+    pub fn get_exclusion_mode(&self) -> ExclusionMode {
+        get_exclusion_mode()
+    }
+
+    #[query]
+    pub fn get_allowed_action_types(&self) -> Vec<ActionType> {
+        describe_action_types(get_allowed_action_types())
+    }
+
+    /// Lists the functions registered in the SNS as of the last fetch.
+    #[query]
+    pub fn get_action_types(&self) -> Vec<ActionType> {
+        describe_action_types(
+            get_registered_functions()
+                .into_iter()
+                .map(|function| function.id)
+                .collect(),
+        )
+    }
+
+    #[query]
+    pub fn get_multisig_policy(&self) -> Option<MultisigPolicy> {
+        get_multisig_policy()
+    }
+
+    /// Lists the sensitive operations waiting for approval that have not expired.
+    #[query]
+    pub fn get_pending_actions(&self) -> Vec<PendingAction> {
+        get_active_pending_actions()
+    }
+
+    #[query]
+    pub fn get_timelock_delay(&self) -> u64 {
+        get_timelock_delay()
+    }
+
+    /// Lists the configuration changes waiting for their activation time.
+    #[query]
+    pub fn get_queued_changes(&self) -> Vec<QueuedChange> {
+        get_queued_changes()
+    }
+
+    #[query]
+    pub fn get_voting_mode(&self) -> VotingMode {
+        get_voting_mode()
+    }
+
+    #[query]
+    pub fn get_council_source(&self) -> CouncilSource {
+        get_council_source()
+    }
+
+    #[query]
+    pub fn get_neuron_combine_rule(&self) -> NeuronCombineRule {
+        get_neuron_combine_rule()
+    }
+
+    /// Lists the council members who can veto proposals, with the action types they can veto.
+    #[query]
+    pub fn get_veto_rights(&self) -> Vec<VetoRight> {
+        get_veto_rights()
+    }
+
+    /// Lists the delegations between council members, including past and upcoming ones.
+    #[query]
+    pub fn get_delegations(&self) -> Vec<Delegation> {
+        get_delegations()
+    }
+
+    #[query]
+    pub fn get_inactivity_rule(&self) -> Option<InactivityRule> {
+        get_inactivity_rule()
+    }
+
+    /// Lists the participation of the council members in the evaluated proposals, including suspensions.
+    #[query]
+    pub fn get_member_activity(&self) -> Vec<MemberActivity> {
+        get_member_activity()
+    }
+
+    /// Lists every suspension and reinstatement of a council member, oldest first.
+    #[query]
+    pub fn get_suspension_log(&self) -> Vec<SuspensionEvent> {
+        get_suspension_log()
+    }
+
+    #[query]
+    pub fn get_early_decision_interval(&self) -> Option<u64> {
+        get_early_decision_interval()
+    }
+
+    #[query]
+    pub fn get_flagged_action_handling(&self) -> FlaggedActionHandling {
+        get_flagged_action_handling()
+    }
+
+    #[query]
+    pub fn get_filter_rules(&self) -> Vec<FilterRule> {
+        get_filter_rules()
+    }
+
+    /// Returns the filter rule that would apply to a proposal with the given fields, if any.
+    #[query]
+    pub fn test_filter_rules(&self, sample: FilterSample) -> Option<FilterRule> {
+        find_matching_rule(&sample)
+    }
+
+    #[query]
+    pub fn get_voting_policies(&self) -> Vec<VotingPolicy> {
+        get_voting_policies()
     }
 
     #[query]
@@ -410,6 +992,37 @@ impl VpProxy {
         let proposal_history = get_proposal_history();
         let excluded_action_ids = get_exclusion_list();
         let neuron_id = NEURON_ID.with(|id| id.borrow().clone());
+        let extension = StableStateExtension {
+            filter_rules: Some(get_filter_rules()),
+            next_filter_rule_id: Some(NEXT_FILTER_RULE_ID.with(|id| id.get())),
+            voting_policies: Some(get_voting_policies()),
+            exclusion_mode: Some(get_exclusion_mode()),
+            allowed_action_ids: Some(get_allowed_action_types()),
+            registered_functions: Some(get_registered_functions()),
+            flagged_action_ids: Some(get_flagged_action_types()),
+            flagged_action_handling: Some(get_flagged_action_handling()),
+            skipped_proposals: Some(get_skipped_proposals()),
+            council_epochs: Some(get_council_epochs()),
+            council_source: Some(get_council_source()),
+            neuron_combine_rule: Some(get_neuron_combine_rule()),
+            upcoming_council_members: Some(get_upcoming_council_members()),
+            role_assignments: Some(get_role_assignments()),
+            multisig_policy: get_multisig_policy(),
+            pending_actions: Some(get_pending_actions()),
+            next_pending_action_id: Some(NEXT_PENDING_ACTION_ID.with(|id| id.get())),
+            timelock_delay_seconds: Some(get_timelock_delay()),
+            queued_changes: Some(get_queued_changes()),
+            next_queued_change_id: Some(NEXT_QUEUED_CHANGE_ID.with(|id| id.get())),
+            audit_log: Some(get_audit_log()),
+            decision_log: Some(get_decision_log()),
+            early_decision_interval_seconds: get_early_decision_interval(),
+            inactivity_rule: get_inactivity_rule(),
+            member_activity: Some(get_member_activity()),
+            suspension_log: Some(get_suspension_log()),
+            delegations: Some(get_delegations()),
+            veto_rights: Some(get_veto_rights()),
+            voting_mode: Some(get_voting_mode()),
+        };
 
         let _ = storage::stable_save((
             governance_canister_id,
@@ -418,6 +1031,7 @@ impl VpProxy {
             proposal_history,
             excluded_action_ids,
             neuron_id,
+            extension,
         ));
     }
 
@@ -430,6 +1044,7 @@ impl VpProxy {
             proposal_history,
             excluded_action_ids,
             neuron_id,
+            extension,
         ): (
             Principal,
             Principal,
@@ -437,14 +1052,16 @@ impl VpProxy {
             Vec<ProxyProposalQuery>,
             Vec<u64>,
             Option<NeuronId>,
+            Option<StableStateExtension>,
         ) = storage::stable_restore().unwrap();
+        let extension = extension.unwrap_or_default();
 
         GOVERNANCE_CANISTER_ID.with(|id| *id.borrow_mut() = governance_canister_id);
         LEDGER_CANISTER_ID.with(|id| *id.borrow_mut() = ledger_canister_id);
 
         COUNCIL_MEMBERS.with(|members| {
             let mut members_borrowed = members.borrow_mut();
-            council_members
+            normalize_restored_members(council_members)
                 .into_iter()
                 .for_each(|member| members_borrowed.push(member));
         });
@@ -452,6 +1069,94 @@ impl VpProxy {
         PROPOSAL_HISTORY.with(|history| *history.borrow_mut() = proposal_history);
         EXCLUDED_ACTION_IDS.with(|ids| *ids.borrow_mut() = excluded_action_ids);
         NEURON_ID.with(|id| *id.borrow_mut() = neuron_id);
+        FILTER_RULES.with(|rules| {
+            *rules.borrow_mut() = extension.filter_rules.unwrap_or_else(default_filter_rules)
+        });
+        NEXT_FILTER_RULE_ID.with(|id| {
+            id.set(extension.next_filter_rule_id.unwrap_or_else(|| {
+                get_filter_rules()
+                    .iter()
+                    .map(|rule| rule.id + 1)
+                    .max()
+                    .unwrap_or(0)
+            }))
+        });
+        VOTING_POLICIES.with(|policies| {
+            *policies.borrow_mut() = extension
+                .voting_policies
+                .unwrap_or_else(default_voting_policies)
+        });
+        EXCLUSION_MODE.with(|mode| mode.set(extension.exclusion_mode.unwrap_or_default()));
+        ALLOWED_ACTION_IDS
+            .with(|ids| *ids.borrow_mut() = extension.allowed_action_ids.unwrap_or_default());
+        REGISTERED_FUNCTIONS.with(|functions| {
+            *functions.borrow_mut() = extension.registered_functions.unwrap_or_default()
+        });
+        FLAGGED_ACTION_IDS
+            .with(|ids| *ids.borrow_mut() = extension.flagged_action_ids.unwrap_or_default());
+        FLAGGED_ACTION_HANDLING
+            .with(|handling| handling.set(extension.flagged_action_handling.unwrap_or_default()));
+        SKIPPED_PROPOSALS.with(|proposals| {
+            *proposals.borrow_mut() = extension.skipped_proposals.unwrap_or_default()
+        });
+        COUNCIL_EPOCHS.with(|epochs| {
+            *epochs.borrow_mut() = extension.council_epochs.unwrap_or_else(|| {
+                // councils from versions without epochs become epoch 0
+                let members = get_council_members();
+                if members.is_empty() {
+                    return vec![];
+                }
+                vec![CouncilEpoch {
+                    epoch: 0,
+                    started_at_seconds: time() / 1_000_000_000,
+                    members: members.clone(),
+                    changes: CouncilDiff {
+                        added: members,
+                        ..Default::default()
+                    },
+                }]
+            })
+        });
+        COUNCIL_SOURCE.with(|source| source.set(extension.council_source.unwrap_or_default()));
+        NEURON_COMBINE_RULE
+            .with(|rule| rule.set(extension.neuron_combine_rule.unwrap_or_default()));
+        UPCOMING_COUNCIL_MEMBERS.with(|members| {
+            *members.borrow_mut() =
+                normalize_restored_members(extension.upcoming_council_members.unwrap_or_default())
+        });
+        ROLE_ASSIGNMENTS.with(|assignments| {
+            *assignments.borrow_mut() = extension.role_assignments.unwrap_or_default()
+        });
+        MULTISIG_POLICY.with(|policy| *policy.borrow_mut() = extension.multisig_policy);
+        PENDING_ACTIONS
+            .with(|actions| *actions.borrow_mut() = extension.pending_actions.unwrap_or_default());
+        NEXT_PENDING_ACTION_ID
+            .with(|id| id.set(extension.next_pending_action_id.unwrap_or_default()));
+        TIMELOCK_DELAY_SECONDS
+            .with(|delay| delay.set(extension.timelock_delay_seconds.unwrap_or_default()));
+        QUEUED_CHANGES
+            .with(|changes| *changes.borrow_mut() = extension.queued_changes.unwrap_or_default());
+        NEXT_QUEUED_CHANGE_ID
+            .with(|id| id.set(extension.next_queued_change_id.unwrap_or_default()));
+        AUDIT_LOG.with(|log| *log.borrow_mut() = extension.audit_log.unwrap_or_default());
+        DECISION_LOG.with(|log| *log.borrow_mut() = extension.decision_log.unwrap_or_default());
+        EARLY_DECISION_INTERVAL_SECONDS
+            .with(|interval| interval.set(extension.early_decision_interval_seconds));
+        INACTIVITY_RULE.with(|rule| rule.set(extension.inactivity_rule));
+        MEMBER_ACTIVITY.with(|activity| {
+            *activity.borrow_mut() = extension.member_activity.unwrap_or_default()
+        });
+        SUSPENSION_LOG.with(|log| *log.borrow_mut() = extension.suspension_log.unwrap_or_default());
+        DELEGATIONS.with(|delegations| {
+            *delegations.borrow_mut() = extension.delegations.unwrap_or_default()
+        });
+        VETO_RIGHTS.with(|rights| *rights.borrow_mut() = extension.veto_rights.unwrap_or_default());
+        VOTING_MODE.with(|mode| *mode.borrow_mut() = extension.voting_mode.unwrap_or_default());
+        certify_decision_log();
+        // timers do not survive upgrades
+        apply_council_terms();
+        schedule_queued_changes();
+        schedule_early_decisions();
     }
 
     pub fn idl() -> Idl {
@@ -0,0 +1,407 @@
+use std::time::Duration;
+
+use ic_exports::{
+    candid::Principal,
+    ic_cdk::{api::time, call, print},
+    ic_cdk_timers::{clear_timer, set_timer},
+};
+use ic_sns_governance::pb::v1::{get_neuron_response, GetNeuron, GetNeuronResponse, NeuronId};
+
+use crate::{
+    activity::{remove_stale_member_activity, rename_member_activity},
+    delegation::{remove_stale_delegations, rename_delegation_member},
+    state::{
+        get_appointed_council_members, get_council_epochs, get_council_members, get_council_source,
+        get_current_council_epoch, get_governance_canister_id, get_upcoming_council_members,
+        COUNCIL_EPOCHS, COUNCIL_MEMBERS, COUNCIL_TERM_TIMER_ID, EARLY_DECISION_INTERVAL_SECONDS,
+        EARLY_DECISION_TIMER_ID, QUEUED_CHANGES, TIMELOCK_TIMER_ID, UPCOMING_COUNCIL_MEMBERS,
+    },
+    types::{CanisterError, CouncilDiff, CouncilEpoch, CouncilMember, CouncilSource},
+    utils::handle_intercanister_call,
+    veto::{remove_stale_veto_rights, rename_veto_right_holder},
+};
+
+/// Number of bytes in an SNS neuron ID.
+const NEURON_ID_LENGTH: usize = 32;
+
+/// Validates a hex encoded neuron ID and lowercases it, the format SNS governance uses to key proposal ballots.
+pub fn normalize_neuron_id(neuron_id: &str) -> Result<String, CanisterError> {
+    let normalized = neuron_id.trim().to_lowercase();
+    if normalized.len() != NEURON_ID_LENGTH * 2
+        || !normalized
+            .chars()
+            .all(|character| character.is_ascii_hexdigit())
+    {
+        return Err(CanisterError::InvalidNeuronId(neuron_id.to_string()));
+    }
+    Ok(normalized)
+}
+
+/// Validates the member's name, neuron IDs and term, returning a normalized copy.
+/// A term must end after it starts, and must not have ended already.
+pub fn normalize_council_member(member: CouncilMember) -> Result<CouncilMember, CanisterError> {
+    let name = member.name.trim().to_string();
+    if name.is_empty() {
+        return Err(CanisterError::InvalidCouncilMemberName(member.name));
+    }
+
+    let neuron_id = normalize_neuron_id(&member.neuron_id)?;
+    let mut additional_neuron_ids: Vec<String> = vec![];
+    for additional_neuron_id in member.additional_neuron_ids.unwrap_or_default() {
+        let additional_neuron_id = normalize_neuron_id(&additional_neuron_id)?;
+        if additional_neuron_id == neuron_id
+            || additional_neuron_ids.contains(&additional_neuron_id)
+        {
+            return Err(CanisterError::DuplicateCouncilMember(name));
+        }
+        additional_neuron_ids.push(additional_neuron_id);
+    }
+
+    if let Some(term_end) = member.term_end {
+        let term_start = member.term_start.unwrap_or_default();
+        if term_end <= term_start || term_end <= time() / 1_000_000_000 {
+            return Err(CanisterError::InvalidCouncilTerm(name));
+        }
+    }
+
+    if member.principal == Some(Principal::anonymous()) {
+        return Err(CanisterError::InvalidCouncilMemberPrincipal(name));
+    }
+
+    Ok(CouncilMember {
+        name,
+        neuron_id,
+        additional_neuron_ids: if additional_neuron_ids.is_empty() {
+            None
+        } else {
+            Some(additional_neuron_ids)
+        },
+        term_start: member.term_start,
+        term_end: member.term_end,
+        principal: member.principal,
+    })
+}
+
+/// Validates a complete council as a unit, returning a normalized copy.
+/// Fails if any member is invalid or if two members share a name or neuron ID.
+pub fn normalize_council(members: Vec<CouncilMember>) -> Result<Vec<CouncilMember>, CanisterError> {
+    let mut council: Vec<CouncilMember> = Vec::with_capacity(members.len());
+    for member in members {
+        let member = normalize_council_member(member)?;
+        if let Some(existing) = council
+            .iter()
+            .find(|existing| is_duplicate(existing, &member))
+        {
+            return Err(CanisterError::DuplicateCouncilMember(existing.name.clone()));
+        }
+        council.push(member);
+    }
+    Ok(council)
+}
+
+/// Normalizes council members restored from stable memory, which older versions stored as entered.
+/// Members that fail to normalize or duplicate an earlier member are dropped and logged, since they could
+/// no longer be matched against ballots, updated or removed. Ended terms are left to the council terms.
+pub fn normalize_restored_members(members: Vec<CouncilMember>) -> Vec<CouncilMember> {
+    let mut restored: Vec<CouncilMember> = Vec::with_capacity(members.len());
+    for member in members {
+        let name = member.name.clone();
+        let term_end = member.term_end;
+        let member = match normalize_council_member(CouncilMember {
+            term_end: None,
+            ..member
+        }) {
+            Ok(member) => CouncilMember { term_end, ..member },
+            Err(err) => {
+                print(format!(
+                    "Dropping restored council member {}. Returned error is: {:#?}",
+                    name, err
+                ));
+                continue;
+            }
+        };
+        if let Some(existing) = restored
+            .iter()
+            .find(|existing| is_duplicate(existing, &member))
+        {
+            print(format!(
+                "Dropping restored council member {}: it duplicates {}.",
+                name, existing.name
+            ));
+            continue;
+        }
+        restored.push(member);
+    }
+    restored
+}
+
+/// Rejects the member if another appointed council member has the same name or principal, or shares a neuron ID.
+/// `replaced_neuron_id` is the primary neuron ID of the entry being updated, which is not compared against.
+pub fn ensure_unique_council_member(
+    member: &CouncilMember,
+    replaced_neuron_id: Option<&str>,
+) -> Result<(), CanisterError> {
+    let duplicate = get_appointed_council_members()
+        .into_iter()
+        .filter(|existing| Some(existing.neuron_id.as_str()) != replaced_neuron_id)
+        .find(|existing| is_duplicate(existing, member));
+
+    match duplicate {
+        Some(existing) => Err(CanisterError::DuplicateCouncilMember(existing.name)),
+        None => Ok(()),
+    }
+}
+
+fn is_duplicate(existing: &CouncilMember, member: &CouncilMember) -> bool {
+    existing.name.eq_ignore_ascii_case(&member.name)
+        || (existing.principal.is_some() && existing.principal == member.principal)
+        || existing
+            .neuron_ids()
+            .any(|neuron_id| member.neuron_ids().any(|other| other == neuron_id))
+}
+
+/// Replaces the appointed council member with the given primary neuron ID.
+/// `neuron_id` and `member` must be normalized.
+pub async fn update_council_member(
+    neuron_id: &str,
+    member: CouncilMember,
+) -> Result<(), CanisterError> {
+    ensure_unique_council_member(&member, Some(neuron_id))?;
+    ensure_new_neurons_exist(std::slice::from_ref(&member)).await?;
+    // the council may have changed while waiting for governance
+    ensure_unique_council_member(&member, Some(neuron_id))?;
+    let mut council = get_appointed_council_members();
+    let existing = council
+        .iter_mut()
+        .find(|existing| existing.neuron_id == neuron_id)
+        .ok_or(CanisterError::CouncilMemberNotFound(neuron_id.to_string()))?;
+    // delegations, veto rights and activity follow the member to their new primary neuron
+    rename_delegation_member(neuron_id, &member.neuron_id);
+    rename_veto_right_holder(neuron_id, &member.neuron_id);
+    rename_member_activity(neuron_id, &member.neuron_id);
+    *existing = member;
+    set_appointed_council(council);
+    Ok(())
+}
+
+/// Adds a normalized member to the appointed council after confirming that its neurons exist in the SNS.
+pub async fn add_council_member(member: CouncilMember) -> Result<(), CanisterError> {
+    ensure_unique_council_member(&member, None)?;
+    ensure_new_neurons_exist(std::slice::from_ref(&member)).await?;
+    // the council may have changed while waiting for governance
+    ensure_unique_council_member(&member, None)?;
+    let mut council = get_appointed_council_members();
+    council.push(member);
+    set_appointed_council(council);
+    Ok(())
+}
+
+pub fn remove_council_member(neuron_id: &str) -> Result<(), CanisterError> {
+    let mut council = get_appointed_council_members();
+    if !council.iter().any(|member| member.neuron_id == neuron_id) {
+        return Err(CanisterError::CouncilMemberNotFound(neuron_id.to_string()));
+    }
+    council.retain(|member| member.neuron_id != neuron_id);
+    set_appointed_council(council);
+    Ok(())
+}
+
+/// Replaces the appointed council with normalized `members` once their new neurons are confirmed.
+pub async fn set_council(members: Vec<CouncilMember>) -> Result<CouncilDiff, CanisterError> {
+    ensure_new_neurons_exist(&members).await?;
+    Ok(set_appointed_council(members))
+}
+
+/// Returns the appointed council member bound to the caller's principal.
+pub fn only_council_member(caller: Principal) -> Result<CouncilMember, CanisterError> {
+    get_appointed_council_members()
+        .into_iter()
+        .find(|member| member.principal == Some(caller))
+        .ok_or(CanisterError::Unauthorized)
+}
+
+/// Confirms that the neurons of `members` that are not part of the appointed council exist in the SNS.
+pub async fn ensure_new_neurons_exist(members: &[CouncilMember]) -> Result<(), CanisterError> {
+    let current = get_appointed_council_members();
+    let new_neuron_ids: Vec<String> = members
+        .iter()
+        .flat_map(|member| member.neuron_ids())
+        .filter(|neuron_id| {
+            !current
+                .iter()
+                .any(|existing| existing.neuron_ids().any(|known| known == *neuron_id))
+        })
+        .cloned()
+        .collect();
+
+    for neuron_id in new_neuron_ids {
+        ensure_neuron_exists(&neuron_id).await?;
+    }
+    Ok(())
+}
+
+/// Replaces the appointed council with `members`, which must be normalized. Members whose term covers
+/// the current time form the council, members whose term has not started wait for it, and members whose
+/// term has ended are dropped, along with their delegations, veto rights and activity. Returns the changes
+/// to the council and schedules the next term change.
+pub fn set_appointed_council(members: Vec<CouncilMember>) -> CouncilDiff {
+    let now = time() / 1_000_000_000;
+    let (council, upcoming): (Vec<CouncilMember>, Vec<CouncilMember>) = members
+        .into_iter()
+        .filter(|member| member.term_end.map_or(true, |end| now < end))
+        .partition(|member| member.is_in_term(now));
+
+    UPCOMING_COUNCIL_MEMBERS.with(|members| *members.borrow_mut() = upcoming);
+    let diff = replace_council(council);
+    remove_stale_delegations();
+    remove_stale_veto_rights();
+    remove_stale_member_activity();
+    schedule_council_terms();
+    diff
+}
+
+/// Empties the council, drops the queued configuration changes and turns early decisions off,
+/// cancelling the timers that would otherwise still act on the previous configuration.
+pub fn emergency_reset() {
+    set_appointed_council(vec![]);
+
+    if let Some(timer_id) = TIMELOCK_TIMER_ID.with(|id| id.borrow_mut().take()) {
+        clear_timer(timer_id);
+    }
+    QUEUED_CHANGES.with(|changes| changes.borrow_mut().clear());
+
+    if let Some(timer_id) = EARLY_DECISION_TIMER_ID.with(|id| id.borrow_mut().take()) {
+        clear_timer(timer_id);
+    }
+    EARLY_DECISION_INTERVAL_SECONDS.with(|interval| interval.set(None));
+}
+
+/// Applies the terms of the appointed council members as of now.
+pub fn apply_council_terms() {
+    let diff = set_appointed_council(get_appointed_council_members());
+    if !diff.is_empty() {
+        print(format!(
+            "Council terms changed the council to epoch {}: {} joined, {} left.",
+            diff.epoch,
+            diff.added.len(),
+            diff.removed.len()
+        ));
+    }
+}
+
+/// Sets a timer for the next start or end of a council member's term, replacing any previous one.
+fn schedule_council_terms() {
+    if let Some(timer_id) = COUNCIL_TERM_TIMER_ID.with(|id| id.borrow_mut().take()) {
+        clear_timer(timer_id);
+    }
+
+    let next_change = get_upcoming_council_members()
+        .iter()
+        .filter_map(|member| member.term_start)
+        .chain(
+            get_council_members()
+                .iter()
+                .filter_map(|member| member.term_end),
+        )
+        .min();
+    let Some(next_change) = next_change else {
+        return;
+    };
+
+    let delay = next_change.saturating_sub(time() / 1_000_000_000);
+    let timer_id = set_timer(Duration::from_secs(delay), apply_council_terms);
+    COUNCIL_TERM_TIMER_ID.with(|id| *id.borrow_mut() = Some(timer_id));
+}
+
+/// Swaps the whole council for `members`, which must be normalized, and starts a new epoch.
+/// Returns the changes; if there are none, the current epoch is kept.
+fn replace_council(members: Vec<CouncilMember>) -> CouncilDiff {
+    let mut diff = council_diff(&get_council_members(), &members);
+    if diff.is_empty() {
+        diff.epoch = get_current_council_epoch();
+        return diff;
+    }
+
+    diff.epoch = get_current_council_epoch() + 1;
+    COUNCIL_EPOCHS.with(|epochs| {
+        epochs.borrow_mut().push(CouncilEpoch {
+            epoch: diff.epoch,
+            started_at_seconds: time() / 1_000_000_000,
+            members: members.clone(),
+            changes: diff.clone(),
+        })
+    });
+    COUNCIL_MEMBERS.with(|council| *council.borrow_mut() = members);
+    diff
+}
+
+/// Returns the council to evaluate a proposal against, with its epoch.
+/// `snapshot_epoch` is the epoch recorded when the proposal was added to the watchlist.
+pub fn get_evaluation_council(snapshot_epoch: Option<u64>) -> (u64, Vec<CouncilMember>) {
+    match (get_council_source(), snapshot_epoch) {
+        (CouncilSource::Snapshot, Some(epoch)) => (epoch, get_council_members_at(epoch)),
+        _ => (get_current_council_epoch(), get_council_members()),
+    }
+}
+
+/// Returns the members of the council in the given epoch. Epoch 0 of a fresh install is the empty council.
+fn get_council_members_at(epoch: u64) -> Vec<CouncilMember> {
+    get_council_epochs()
+        .into_iter()
+        .find(|council_epoch| council_epoch.epoch == epoch)
+        .map(|council_epoch| council_epoch.members)
+        .unwrap_or_default()
+}
+
+/// Computes the changes from `current` to `next`, matching members by primary neuron ID.
+fn council_diff(current: &[CouncilMember], next: &[CouncilMember]) -> CouncilDiff {
+    let find = |members: &[CouncilMember], neuron_id: &str| {
+        members
+            .iter()
+            .find(|member| member.neuron_id == neuron_id)
+            .cloned()
+    };
+
+    let mut diff = CouncilDiff::default();
+    for member in next {
+        match find(current, &member.neuron_id) {
+            None => diff.added.push(member.clone()),
+            Some(previous) if previous != *member => diff.updated.push(member.clone()),
+            Some(_) => {}
+        }
+    }
+    diff.removed = current
+        .iter()
+        .filter(|member| find(next, &member.neuron_id).is_none())
+        .cloned()
+        .collect();
+    diff
+}
+
+/// Confirms through the governance canister that the neuron exists in the configured SNS.
+/// `neuron_id` must be normalized.
+pub async fn ensure_neuron_exists(neuron_id: &str) -> Result<(), CanisterError> {
+    let governance_canister_id = get_governance_canister_id()?;
+
+    let get_neuron_arg = GetNeuron {
+        neuron_id: Some(NeuronId {
+            id: neuron_id_from_hex(neuron_id),
+        }),
+    };
+
+    let get_neuron_response = call(governance_canister_id, "get_neuron", (get_neuron_arg,)).await;
+
+    let get_neuron_handled = handle_intercanister_call::<GetNeuronResponse>(get_neuron_response)?;
+
+    match get_neuron_handled.result {
+        Some(get_neuron_response::Result::Neuron(_)) => Ok(()),
+        _ => Err(CanisterError::NeuronNotFound(neuron_id.to_string())),
+    }
+}
+
+fn neuron_id_from_hex(neuron_id: &str) -> Vec<u8> {
+    (0..neuron_id.len())
+        .step_by(2)
+        .filter_map(|index| u8::from_str_radix(&neuron_id[index..index + 2], 16).ok())
+        .collect()
+}
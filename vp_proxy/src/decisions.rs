@@ -0,0 +1,74 @@
+use ic_exports::{
+    candid::Encode,
+    ic_cdk::api::{data_certificate, set_certified_data},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    state::DECISION_LOG,
+    types::{DecisionEntry, DecisionLogPage, DecisionRecord},
+};
+
+/// Maximum number of entries returned by a single `get_decision_log` call.
+pub const MAX_DECISION_LOG_PAGE_SIZE: u64 = 100;
+
+/// Hash the first entry links to.
+const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// Appends a decision to the log and certifies the new chain tip.
+/// The entry's hash is the SHA-256 of the previous entry's hash followed by the candid encoding of the record.
+pub fn record_decision(record: DecisionRecord) {
+    DECISION_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let previous_hash = log
+            .last()
+            .map_or(GENESIS_HASH.to_vec(), |entry| entry.hash.clone());
+        let encoded = Encode!(&record).expect("decision records are candid encodable");
+        let hash = Sha256::new()
+            .chain_update(&previous_hash)
+            .chain_update(encoded)
+            .finalize()
+            .to_vec();
+        log.push(DecisionEntry {
+            index: log.len() as u64,
+            record,
+            previous_hash,
+            hash,
+        });
+    });
+    certify_decision_log();
+}
+
+/// Sets the chain tip as the canister's certified data. Certified data does not survive upgrades.
+pub fn certify_decision_log() {
+    let tip = get_decision_log_tip();
+    set_certified_data(&tip);
+}
+
+fn get_decision_log_tip() -> Vec<u8> {
+    DECISION_LOG.with(|log| {
+        log.borrow()
+            .last()
+            .map_or(GENESIS_HASH.to_vec(), |entry| entry.hash.clone())
+    })
+}
+
+/// Returns up to `limit` entries starting at `offset`, with the certificate of the chain tip.
+/// Must be called from a query, since certificates are only available there.
+pub fn get_decision_log_page(offset: u64, limit: u64) -> DecisionLogPage {
+    let limit = limit.min(MAX_DECISION_LOG_PAGE_SIZE) as usize;
+    DECISION_LOG.with(|log| {
+        let log = log.borrow();
+        DecisionLogPage {
+            entries: log
+                .iter()
+                .skip(offset.try_into().unwrap_or(usize::MAX))
+                .take(limit)
+                .cloned()
+                .collect(),
+            total: log.len() as u64,
+            tip_hash: get_decision_log_tip(),
+            certificate: data_certificate(),
+        }
+    })
+}
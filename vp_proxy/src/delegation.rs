@@ -0,0 +1,116 @@
+use ic_exports::ic_cdk::api::time;
+
+use crate::{
+    council::normalize_neuron_id,
+    state::{get_appointed_council_members, DELEGATIONS},
+    types::{CanisterError, Delegation},
+};
+
+/// Validates a delegation and adds it, replacing the delegator's previous delegation.
+/// Both members must be on the council, and a delegation may not lead to another one in an overlapping
+/// window, which rules out chains and cycles.
+pub fn set_delegation(delegation: Delegation) -> Result<(), CanisterError> {
+    let delegation = normalize_delegation(delegation)?;
+    let others: Vec<Delegation> = get_delegations()
+        .into_iter()
+        .filter(|other| other.delegator != delegation.delegator)
+        .collect();
+    let chained = others.iter().any(|other| {
+        overlaps(other, &delegation)
+            && (other.delegator == delegation.delegate || other.delegate == delegation.delegator)
+    });
+    if chained {
+        return Err(CanisterError::InvalidDelegation(
+            "delegations cannot be chained".to_string(),
+        ));
+    }
+
+    DELEGATIONS.with(|delegations| {
+        let mut delegations = delegations.borrow_mut();
+        delegations.retain(|existing| existing.delegator != delegation.delegator);
+        delegations.push(delegation);
+    });
+    Ok(())
+}
+
+/// Removes the delegation of the member with the given primary neuron.
+pub fn remove_delegation(delegator: &str) -> Result<(), CanisterError> {
+    DELEGATIONS.with(|delegations| {
+        let mut delegations = delegations.borrow_mut();
+        let count = delegations.len();
+        delegations.retain(|delegation| delegation.delegator != delegator);
+        if delegations.len() == count {
+            return Err(CanisterError::DelegationNotFound(delegator.to_string()));
+        }
+        Ok(())
+    })
+}
+
+/// Moves the delegations of the member whose primary neuron changed from `from` to `to`.
+pub fn rename_delegation_member(from: &str, to: &str) {
+    DELEGATIONS.with(|delegations| {
+        for delegation in delegations.borrow_mut().iter_mut() {
+            if delegation.delegator == from {
+                delegation.delegator = to.to_string();
+            }
+            if delegation.delegate == from {
+                delegation.delegate = to.to_string();
+            }
+        }
+    });
+}
+
+/// Drops the delegations whose delegator or delegate is no longer an appointed council member.
+pub fn remove_stale_delegations() {
+    let members = get_appointed_council_members();
+    let is_member = |neuron_id: &str| members.iter().any(|member| member.neuron_id == neuron_id);
+    DELEGATIONS.with(|delegations| {
+        delegations.borrow_mut().retain(|delegation| {
+            is_member(&delegation.delegator) && is_member(&delegation.delegate)
+        })
+    });
+}
+
+pub fn get_delegations() -> Vec<Delegation> {
+    DELEGATIONS.with(|delegations| delegations.borrow().clone())
+}
+
+/// Returns the delegations whose window covers `now`, in seconds since the epoch.
+pub fn get_active_delegations(now: u64) -> Vec<Delegation> {
+    get_delegations()
+        .into_iter()
+        .filter(|delegation| delegation.starts_at <= now && now < delegation.ends_at)
+        .collect()
+}
+
+pub fn normalize_delegation(delegation: Delegation) -> Result<Delegation, CanisterError> {
+    let delegator = normalize_neuron_id(&delegation.delegator)?;
+    let delegate = normalize_neuron_id(&delegation.delegate)?;
+    if delegator == delegate {
+        return Err(CanisterError::InvalidDelegation(
+            "members cannot delegate to themselves".to_string(),
+        ));
+    }
+    if delegation.starts_at >= delegation.ends_at || delegation.ends_at <= time() / 1_000_000_000 {
+        return Err(CanisterError::InvalidDelegation(
+            "the delegation must end after it starts, in the future".to_string(),
+        ));
+    }
+
+    let members = get_appointed_council_members();
+    for neuron_id in [&delegator, &delegate] {
+        if !members.iter().any(|member| &member.neuron_id == neuron_id) {
+            return Err(CanisterError::CouncilMemberNotFound(neuron_id.clone()));
+        }
+    }
+
+    Ok(Delegation {
+        delegator,
+        delegate,
+        ..delegation
+    })
+}
+
+fn overlaps(first: &Delegation, second: &Delegation) -> bool {
+    first.starts_at < second.ends_at && second.starts_at < first.ends_at
+}
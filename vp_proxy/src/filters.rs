@@ -0,0 +1,110 @@
+use ic_sns_governance::pb::v1::ProposalData;
+
+use crate::{
+    council::normalize_neuron_id,
+    state::{get_filter_rules, get_voting_policy, FILTER_RULES, NEXT_FILTER_RULE_ID},
+    types::{CanisterError, FilterAction, FilterMatcher, FilterRule, FilterSample},
+    utils::neuron_id_to_hex,
+};
+
+impl From<&ProposalData> for FilterSample {
+    fn from(value: &ProposalData) -> Self {
+        let proposal = value.proposal.clone().unwrap_or_default();
+        Self {
+            title: proposal.title,
+            summary: proposal.summary,
+            url: proposal.url,
+            proposer: value
+                .proposer
+                .as_ref()
+                .map(|neuron_id| neuron_id_to_hex(&neuron_id.id)),
+        }
+    }
+}
+
+/// Validates the proposer neuron ID of a matcher and lowercases it, like council neuron IDs.
+pub fn normalize_filter_matcher(matcher: FilterMatcher) -> Result<FilterMatcher, CanisterError> {
+    match matcher {
+        FilterMatcher::ProposerNeuron(neuron_id) => Ok(FilterMatcher::ProposerNeuron(
+            normalize_neuron_id(&neuron_id)?,
+        )),
+        matcher => Ok(matcher),
+    }
+}
+
+/// Rejects actions assigning a voting policy that does not exist.
+pub fn validate_filter_action(action: &FilterAction) -> Result<(), CanisterError> {
+    if let FilterAction::ApplyPolicy(name) = action {
+        get_voting_policy(Some(name))?;
+    }
+    Ok(())
+}
+
+/// Validates a rule and appends it with a new ID, returning the ID.
+pub fn add_filter_rule(matcher: FilterMatcher, action: FilterAction) -> Result<u64, CanisterError> {
+    let matcher = normalize_filter_matcher(matcher)?;
+    validate_filter_action(&action)?;
+    let id = NEXT_FILTER_RULE_ID.with(|id| id.replace(id.get() + 1));
+    FILTER_RULES.with(|rules| {
+        rules.borrow_mut().push(FilterRule {
+            id,
+            matcher,
+            action,
+        })
+    });
+    Ok(id)
+}
+
+pub fn remove_filter_rule(id: u64) -> Result<(), CanisterError> {
+    FILTER_RULES.with(|rules| {
+        let mut rules = rules.borrow_mut();
+        if !rules.iter().any(|rule| rule.id == id) {
+            return Err(CanisterError::FilterRuleNotFound(id));
+        }
+        rules.retain(|rule| rule.id != id);
+        Ok(())
+    })
+}
+
+/// Returns the first filter rule that matches the proposal, if any.
+pub fn find_matching_rule(sample: &FilterSample) -> Option<FilterRule> {
+    get_filter_rules()
+        .into_iter()
+        .find(|rule| matches(&rule.matcher, sample))
+}
+
+fn matches(matcher: &FilterMatcher, sample: &FilterSample) -> bool {
+    match matcher {
+        FilterMatcher::TitlePrefix(prefix) => sample.title.starts_with(prefix.as_str()),
+        FilterMatcher::TitleContains(text) => sample.title.contains(text.as_str()),
+        FilterMatcher::SummaryKeyword(keyword) => sample
+            .summary
+            .to_lowercase()
+            .contains(&keyword.to_lowercase()),
+        FilterMatcher::UrlDomain(domain) => match url_host(&sample.url) {
+            Some(host) => {
+                let domain = domain.to_lowercase();
+                host == domain || host.ends_with(&format!(".{}", domain))
+            }
+            None => false,
+        },
+        FilterMatcher::ProposerNeuron(neuron_id) => sample
+            .proposer
+            .as_ref()
+            .is_some_and(|proposer| proposer.eq_ignore_ascii_case(neuron_id)),
+    }
+}
+
+/// Extracts the lowercased host from a URL such as `https://forum.example.com:443/t/123`.
+fn url_host(url: &str) -> Option<String> {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = without_scheme.split(['/', '?', '#']).next()?;
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host_and_port.split(':').next()?;
+    if host.is_empty() {
+        return None;
+    }
+    Some(host.to_lowercase())
+}
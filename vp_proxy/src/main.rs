@@ -1,8 +1,22 @@
+mod actions;
+mod activity;
+mod approvals;
+mod audit;
 mod canister;
+mod council;
+mod decisions;
+mod delegation;
+mod filters;
+mod mirror;
+mod neuron;
 mod proposals;
 mod state;
+mod stats;
+mod timelock;
 mod types;
 mod utils;
+mod veto;
+mod voting;
 
 use crate::canister::VpProxy;
 
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use ic_exports::ic_cdk::api::time;
+use ic_sns_governance::pb::v1::{Ballot, Vote};
+
+use crate::{
+    council::{ensure_neuron_exists, normalize_neuron_id},
+    state::VOTING_MODE,
+    types::{CanisterError, EvaluationRecord, VotingMode},
+};
+
+/// Sets the voting mode, which must be normalized. The neurons to mirror must exist in the SNS.
+pub async fn set_voting_mode(mode: VotingMode) -> Result<(), CanisterError> {
+    if let VotingMode::Mirror(neuron_ids) = &mode {
+        for neuron_id in neuron_ids {
+            ensure_neuron_exists(neuron_id).await?;
+        }
+    }
+    VOTING_MODE.with(|voting_mode| *voting_mode.borrow_mut() = mode);
+    Ok(())
+}
+
+/// Validates the voting mode, returning a normalized copy. The neurons to mirror must be distinct.
+pub fn normalize_voting_mode(mode: VotingMode) -> Result<VotingMode, CanisterError> {
+    Ok(match mode {
+        VotingMode::Council => VotingMode::Council,
+        VotingMode::Mirror(neuron_ids) => {
+            let mut normalized: Vec<String> = vec![];
+            for neuron_id in neuron_ids {
+                let neuron_id = normalize_neuron_id(&neuron_id)?;
+                if normalized.contains(&neuron_id) {
+                    return Err(CanisterError::InvalidVotingMode(format!(
+                        "neuron {} is listed twice",
+                        neuron_id
+                    )));
+                }
+                normalized.push(neuron_id);
+            }
+            if normalized.is_empty() {
+                return Err(CanisterError::InvalidVotingMode(
+                    "at least one neuron to mirror is required".to_string(),
+                ));
+            }
+            VotingMode::Mirror(normalized)
+        }
+    })
+}
+
+/// The ballots of the mirrored neurons on a proposal.
+pub struct MirrorTally {
+    /// The mirrored neurons in their order: the lead, then the fallbacks.
+    pub neuron_ids: Vec<String>,
+    /// The first neuron in the list that voted, with its vote.
+    pub lead: Option<(String, Vote)>,
+    /// Whether the lead may still change: a neuron listed before it, or any neuron if none voted, is
+    /// eligible and has not voted yet.
+    pub provisional: bool,
+    /// Mirrored neurons with a ballot on the proposal.
+    pub eligible: u64,
+    pub voted: u64,
+    pub yes: u64,
+}
+
+/// Looks up the ballots of the mirrored neurons in their order. Neurons without a ballot, e.g. because they
+/// were created after the proposal, are passed over.
+pub fn tally_mirror(neuron_ids: &[String], ballots: &BTreeMap<String, Ballot>) -> MirrorTally {
+    let mut tally = MirrorTally {
+        neuron_ids: neuron_ids.to_vec(),
+        lead: None,
+        provisional: false,
+        eligible: 0,
+        voted: 0,
+        yes: 0,
+    };
+
+    for neuron_id in neuron_ids {
+        let Some(ballot) = ballots.get(neuron_id) else {
+            continue;
+        };
+        tally.eligible += 1;
+        let vote = if ballot.vote == Vote::Yes as i32 {
+            Vote::Yes
+        } else if ballot.vote == Vote::No as i32 {
+            Vote::No
+        } else {
+            if tally.lead.is_none() {
+                tally.provisional = true;
+            }
+            continue;
+        };
+        tally.voted += 1;
+        if vote == Vote::Yes {
+            tally.yes += 1;
+        }
+        if tally.lead.is_none() {
+            tally.lead = Some((neuron_id.clone(), vote));
+        }
+    }
+
+    tally
+}
+
+impl From<MirrorTally> for EvaluationRecord {
+    fn from(tally: MirrorTally) -> Self {
+        Self {
+            eligible_members: tally.eligible,
+            voted: tally.voted,
+            yes: tally.yes,
+            ineligible_members: vec![],
+            no: tally.voted - tally.yes,
+            not_voted: tally.eligible - tally.voted,
+            member_ballots: vec![],
+            suspended_members: vec![],
+            vetoed_by: vec![],
+            mirrored_neuron: tally.lead.map(|(neuron_id, _)| neuron_id),
+            mirrored_neurons: tally.neuron_ids,
+            quorum_percent: None,
+            approval_percent: None,
+            evaluated_at_seconds: time() / 1_000_000_000,
+            vote_sent: None,
+            governance_response: None,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use ic_exports::{
+    candid::Nat,
+    ic_cdk::{call, id},
+};
+use ic_nervous_system_common::ledger;
+use ic_sns_governance::pb::v1::{
+    manage_neuron::{
+        self,
+        claim_or_refresh::{By, MemoAndController},
+        configure::Operation,
+        ClaimOrRefresh, Configure, IncreaseDissolveDelay,
+    },
+    ManageNeuron, ManageNeuronResponse, NeuronId,
+};
+use icrc_ledger_types::icrc1::{
+    account::Account,
+    transfer::{Memo, TransferArg, TransferError},
+};
+
+use crate::{
+    state::{get_governance_canister_id, get_ledger_canister_id, get_neuron, NEURON_ID},
+    types::CanisterError,
+    utils::handle_intercanister_call,
+};
+
+/// Stakes the amount as the proxy's neuron and claims it in governance.
+pub async fn create_neuron(amount: Nat, nonce: u64) -> Result<NeuronId, CanisterError> {
+    if get_neuron().is_ok() {
+        return Err(CanisterError::NeuronAlreadySet);
+    }
+
+    // transfers all CONF tokens to the neuron's subaccount under the governance canister id
+    let subaccount = ledger::compute_neuron_staking_subaccount(id().into(), nonce);
+    let governance_canister_id = get_governance_canister_id()?;
+    let ledger_canister_id = get_ledger_canister_id()?;
+
+    let transfer_args = TransferArg {
+        from_subaccount: None,
+        to: Account {
+            owner: governance_canister_id,
+            subaccount: Some(subaccount.0),
+        },
+        fee: None,
+        created_at_time: None,
+        memo: Some(Memo::from(nonce)),
+        amount,
+    };
+
+    let transfer_response = call(ledger_canister_id, "icrc1_transfer", (transfer_args,)).await;
+
+    match handle_intercanister_call::<Result<Nat, TransferError>>(transfer_response)? {
+        Err(err) => Err(CanisterError::Unknown(format!(
+            "Error occured on token transfer: {:#?}",
+            err
+        ))),
+        _ => Ok(()),
+    }?;
+
+    // claim neuron
+    let neuron_claim_args = ManageNeuron {
+        subaccount: subaccount.to_vec(),
+        command: Some(manage_neuron::Command::ClaimOrRefresh(ClaimOrRefresh {
+            by: Some(By::MemoAndController(MemoAndController {
+                memo: nonce,
+                controller: Some(id().into()),
+            })),
+        })),
+    };
+
+    let claim_response = call(
+        governance_canister_id,
+        "manage_neuron",
+        (neuron_claim_args,),
+    )
+    .await;
+
+    let manage_neuron_response = handle_intercanister_call::<ManageNeuronResponse>(claim_response)?;
+
+    if let Some(command) = manage_neuron_response.command {
+        let neuron_id = match command {
+            ic_sns_governance::pb::v1::manage_neuron_response::Command::ClaimOrRefresh(
+                claim_or_refresh_response,
+            ) => Ok(claim_or_refresh_response.refreshed_neuron_id),
+            _ => Err(CanisterError::Unknown(
+                "Could not handle the manage neuron response".to_string(),
+            )),
+        }?;
+
+        if let Some(neuron_id_unwrapped) = neuron_id {
+            NEURON_ID.with(|id| *id.borrow_mut() = Some(neuron_id_unwrapped.clone()));
+            return Ok(neuron_id_unwrapped);
+        }
+
+        return Err(CanisterError::Unknown(
+            "Neuron Id couldn't be generated.".to_string(),
+        ));
+    } else {
+        return Err(CanisterError::Unknown(
+            "Could not handle the manage neuron response".to_string(),
+        ));
+    }
+}
+
+pub async fn increase_dissolve_delay(delay: u32) -> Result<(), CanisterError> {
+    let neuron_id = get_neuron()?;
+    let governance_canister_id = get_governance_canister_id()?;
+
+    let neuron_claim_args = ManageNeuron {
+        subaccount: neuron_id.id,
+        command: Some(manage_neuron::Command::Configure(Configure {
+            operation: Some(Operation::IncreaseDissolveDelay(IncreaseDissolveDelay {
+                additional_dissolve_delay_seconds: delay,
+            })),
+        })),
+    };
+
+    let claim_response = call(
+        governance_canister_id,
+        "manage_neuron",
+        (neuron_claim_args,),
+    )
+    .await;
+
+    let manage_neuron_response = handle_intercanister_call::<ManageNeuronResponse>(claim_response)?;
+
+    if let Some(command) = manage_neuron_response.command {
+        return match command {
+            ic_sns_governance::pb::v1::manage_neuron_response::Command::Configure(_) => Ok(()),
+            _ => Err(CanisterError::Unknown(
+                "Could not handle the manage neuron response".to_string(),
+            )),
+        };
+    }
+    return Err(CanisterError::Unknown(
+        "Could not handle the manage neuron response".to_string(),
+    ));
+}
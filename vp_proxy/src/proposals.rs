@@ -1,28 +1,45 @@
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 use ic_exports::{
     ic_cdk::{api::time, call, print, spawn},
-    ic_cdk_timers::set_timer,
+    ic_cdk_timers::{clear_timer, set_timer, set_timer_interval, TimerId},
 };
 use ic_sns_governance::pb::v1::{
-    GetProposal, GetProposalResponse, ListProposals, ListProposalsResponse, ProposalData,
-    ProposalId,
+    get_proposal_response, Ballot, GetProposal, GetProposalResponse, ListProposals,
+    ListProposalsResponse, ProposalData, ProposalId, Vote,
 };
 
 use crate::{
+    actions::refresh_registered_functions,
+    activity::record_member_activity,
+    council::get_evaluation_council,
+    decisions::record_decision,
+    delegation::get_active_delegations,
+    filters::find_matching_rule,
+    mirror::tally_mirror,
     state::{
-        change_proposal_lock, get_council_members, get_governance_canister_id,
-        get_last_proposal_id, get_max_retries, get_watch_lock, EXCLUDED_ACTION_IDS, LAST_PROPOSAL,
-        PROPOSAL_HISTORY, WATCHING_PROPOSALS,
+        change_proposal_lock, get_council_source, get_current_council_epoch,
+        get_early_decision_interval, get_fetcher_timer_id, get_governance_canister_id,
+        get_inactivity_rule, get_last_proposal_id, get_max_retries, get_neuron_combine_rule,
+        get_proposal_watchlist, get_suspended_neuron_ids, get_voting_mode, get_voting_policy,
+        get_watch_lock, is_action_permitted, EARLY_DECISION_INTERVAL_SECONDS,
+        EARLY_DECISION_TIMER_ID, FETCHER_TIMER_ID, LAST_PROPOSAL, PROPOSAL_HISTORY,
+        SKIPPED_PROPOSALS, WATCHING_PROPOSALS, WATCH_LOCK,
+    },
+    types::{
+        BallotChoice, CanisterError, CouncilMember, CouncilSource, DecisionRecord, Delegation,
+        EvaluationRecord, FilterAction, FilterSample, LiveTally, ParticipationStatus,
+        ProxyProposal, ProxyProposalQuery, SkipReason, VoteResponse, VotingMode, VotingPolicy,
     },
-    types::{CanisterError, ParticipationStatus, ProxyProposal, ProxyProposalQuery},
     utils::{handle_intercanister_call, vote},
+    veto::get_veto_holders,
+    voting::tally_council,
 };
 
 pub async fn check_proposals() -> Result<(), CanisterError> {
     let last_proposal = get_last_proposal_id()?;
     let governance_canister_id = get_governance_canister_id()?;
-    let excluded_actions = EXCLUDED_ACTION_IDS.with(|vector| vector.borrow().clone());
+    refresh_functions_for_scan().await;
     let mut before_proposal: Option<ProposalId> = None;
 
     // we start a loop that continues until it reaches a point either before the last proposal indexed in the previous 24h cycle or the same proposal itself
@@ -31,7 +48,8 @@ pub async fn check_proposals() -> Result<(), CanisterError> {
         let list_proposals_arg = ListProposals {
             limit: 100, // maximum limit set by dfinity's sns project
             before_proposal,
-            exclude_type: excluded_actions.clone(),
+            // excluded actions are skipped by the proxy itself, so that they are recorded
+            exclude_type: vec![],
             include_reward_status: vec![],
             include_status: vec![],
         };
@@ -84,117 +102,248 @@ async fn handle_proposal(
                 creation_timestamp: proposals[0].proposal_creation_timestamp_seconds,
                 participation_status: ParticipationStatus::Undecided,
                 timer_scheduled_for: None,
+                policy: None,
+                council_epoch: None,
+                evaluation: None,
+                title: None,
+                url: None,
             });
         });
         *before_proposal = None;
         Ok(true)
-    } else if proposal
-        .proposal
-        .as_ref()
-        .unwrap()
-        .title
-        .starts_with("CONFIGURE COUNCIL NEURON")
-    {
-        // This is related to council neuron proxy configurations. Ignore.
+    } else if !is_action_permitted(proposal.action) {
+        record_skipped_proposal(proposal, SkipReason::ExcludedAction);
+        *before_proposal = proposal.id;
         return Ok(false);
     } else if proposal.reward_event_end_timestamp_seconds.is_some() {
+        record_skipped_proposal(proposal, SkipReason::AlreadyClosed);
+        *before_proposal = proposal.id;
         return Ok(false);
     } else {
+        let mut policy: Option<String> = None;
+        if let Some(rule) = find_matching_rule(&FilterSample::from(proposal)) {
+            match rule.action {
+                FilterAction::Skip => {
+                    record_skipped_proposal(proposal, SkipReason::FilterRule { rule_id: rule.id });
+                    *before_proposal = proposal.id;
+                    return Ok(false);
+                }
+                FilterAction::ForceAbstain => {
+                    print(format!(
+                        "Abstaining from proposal id {} as required by filter rule {}.",
+                        proposal.id.unwrap().id,
+                        rule.id
+                    ));
+                    record_outcome(
+                        ProxyProposalQuery {
+                            id: proposal.id.unwrap(),
+                            action: proposal.action,
+                            creation_timestamp: proposal.proposal_creation_timestamp_seconds,
+                            participation_status: ParticipationStatus::Abstained,
+                            timer_scheduled_for: None,
+                            policy: None,
+                            council_epoch: None,
+                            evaluation: None,
+                            title: proposal_title(proposal),
+                            url: proposal_url(proposal),
+                        },
+                        None,
+                    );
+                    *before_proposal = proposal.id;
+                    return Ok(false);
+                }
+                FilterAction::ApplyPolicy(name) => policy = Some(name),
+            }
+        }
+
         let current_time = time() / 1_000_000_000;
-        let deadline = proposal.initial_voting_period_seconds
-            + proposal.proposal_creation_timestamp_seconds
-            - 3600;
+        let deadline = evaluation_deadline(proposal);
 
         if deadline <= current_time {
+            record_skipped_proposal(proposal, SkipReason::DeadlinePassed);
+            *before_proposal = proposal.id;
             return Ok(false);
         }
 
         let remaining_time = deadline - current_time;
 
         let proposal_id = proposal.id.unwrap();
-        let proposal_action = proposal.action;
-        let proposal_creation_timestamp = proposal.proposal_creation_timestamp_seconds;
+        let watched_proposal = ProxyProposalQuery {
+            id: proposal_id,
+            action: proposal.action,
+            creation_timestamp: proposal.proposal_creation_timestamp_seconds,
+            participation_status: ParticipationStatus::Undecided,
+            timer_scheduled_for: Some(deadline),
+            policy,
+            council_epoch: Some(get_current_council_epoch()),
+            evaluation: None,
+            title: proposal_title(proposal),
+            url: proposal_url(proposal),
+        };
         print(format!(
             "Scheduling vote on proposal id {} in {} seconds.",
             proposal_id.id, remaining_time
         ));
-        let proposal_timer_id = set_timer(Duration::from_secs(remaining_time), move || {
-            let proposal_id = proposal_id.clone();
-            spawn(async move {
-                let max_retries = get_max_retries();
-                for attempt in 1..=max_retries {
-                    let checked_proposal =
-                        vote_on_proposal(proposal_id, proposal_action, proposal_creation_timestamp)
-                            .await;
-                    if let Err(err) = checked_proposal {
-                        let _ = change_proposal_lock(proposal_id, false);
-                        if attempt + 1 > max_retries {
-                            print(format!(
-                                "Voting failed for proposal id {}. Retry number {}. Returned error is: {:#?}. No more retries. Adding proposal to history with FailedToVote participation status.",
-                                proposal_id.id,
-                                attempt,
-                                err
-                            ));
-
-                            // remove this proposal from the watchlist
-                            WATCHING_PROPOSALS.with(|proposals| {
-                                proposals
-                                    .borrow_mut()
-                                    .retain(|proposal| proposal.id != proposal_id)
-                            });
-
-                            // add this proposal and the final decision of the canister to the history
-                            PROPOSAL_HISTORY.with(|proposals| {
-                                proposals.borrow_mut().push(ProxyProposalQuery {
-                                    id: proposal_id,
-                                    action: proposal_action,
-                                    creation_timestamp: proposal_creation_timestamp,
-                                    participation_status: ParticipationStatus::FailedToVote,
-                                    timer_scheduled_for: None,
-                                });
-                            });
-                        } else {
-                            print(format!(
-                                "Voting failed for proposal id {}. Retry number {}. Returned error is: {:#?}. Retrying...",
-                                proposal_id.id,
-                                attempt,
-                                err
-                            ));
-                        }
-                    } else if let Ok(status) = checked_proposal {
+        let proposal_timer_id = schedule_evaluation(watched_proposal.clone(), deadline);
+
+        WATCHING_PROPOSALS.with(|proposals| {
+            let mut proxy_proposal = ProxyProposal::from(watched_proposal);
+            proxy_proposal.timer_id = Some(proposal_timer_id);
+            proxy_proposal.timer_scheduled_for = Some(deadline);
+            proposals.borrow_mut().push(proxy_proposal);
+        });
+
+        *before_proposal = proposal.id;
+        Ok(false)
+    }
+}
+
+/// Time at which the proxy evaluates a proposal: one hour before its initial voting period ends.
+fn evaluation_deadline(proposal: &ProposalData) -> u64 {
+    proposal.initial_voting_period_seconds + proposal.proposal_creation_timestamp_seconds - 3600
+}
+
+/// Sets the timer voting on a watched proposal at `deadline`, retrying up to the maximum number of retries.
+/// The timer leaves the proposal alone while another evaluation holds its lock: that evaluation reschedules
+/// the timer if it fails.
+fn schedule_evaluation(proposal: ProxyProposalQuery, deadline: u64) -> TimerId {
+    let remaining_time = deadline.saturating_sub(time() / 1_000_000_000);
+    set_timer(Duration::from_secs(remaining_time), move || {
+        let proposal = proposal.clone();
+        spawn(async move {
+            let max_retries = get_max_retries();
+            for attempt in 1..=max_retries {
+                let checked_proposal = vote_on_proposal(proposal.clone()).await;
+                if let Err(err) = checked_proposal {
+                    if let CanisterError::ProposalLocked(_)
+                    | CanisterError::ProposalIsNotInWatchlist(_) = err
+                    {
                         print(format!(
-                            "Voted successfully for proposal id {}. The final vote is: {:#?}",
-                            proposal_id.id, status
+                            "Proposal id {} is being evaluated or was already decided. Returned error is: {:#?}",
+                            proposal.id.id, err
                         ));
                         break;
                     }
+                    let _ = change_proposal_lock(proposal.id, false);
+                    if attempt + 1 > max_retries {
+                        print(format!(
+                            "Voting failed for proposal id {}. Retry number {}. Returned error is: {:#?}. No more retries. Adding proposal to history with FailedToVote participation status.",
+                            proposal.id.id,
+                            attempt,
+                            err
+                        ));
+
+                        // remove this proposal from the watchlist, keeping the evaluation of the last attempt
+                        let evaluation = WATCHING_PROPOSALS.with(|proposals| {
+                            let mut proposals = proposals.borrow_mut();
+                            let evaluation = proposals
+                                .iter()
+                                .find(|watched| watched.id == proposal.id)
+                                .and_then(|watched| watched.evaluation.clone());
+                            proposals.retain(|watched| watched.id != proposal.id);
+                            evaluation
+                        });
+
+                        // add this proposal and the final decision of the canister to the history
+                        record_outcome(
+                            ProxyProposalQuery {
+                                participation_status: ParticipationStatus::FailedToVote,
+                                timer_scheduled_for: None,
+                                evaluation,
+                                ..proposal.clone()
+                            },
+                            None,
+                        );
+                    } else {
+                        print(format!(
+                            "Voting failed for proposal id {}. Retry number {}. Returned error is: {:#?}. Retrying...",
+                            proposal.id.id,
+                            attempt,
+                            err
+                        ));
+                    }
+                } else if let Ok(status) = checked_proposal {
+                    print(format!(
+                        "Voted successfully for proposal id {}. The final vote is: {:#?}",
+                        proposal.id.id, status
+                    ));
+                    break;
                 }
-            })
-        });
+            }
+        })
+    })
+}
 
-        WATCHING_PROPOSALS.with(|proposals| {
-            let proxy_proposal = ProxyProposal {
-                id: proposal_id,
-                action: proposal.action,
-                creation_timestamp: proposal.proposal_creation_timestamp_seconds,
-                timer_id: Some(proposal_timer_id),
-                participation_status: ParticipationStatus::Undecided,
-                lock: false,
-                timer_scheduled_for: Some(deadline),
-            };
-            proposals.borrow_mut().push(proxy_proposal);
+/// Adds the proposal to the skipped proposals, unless it was recorded before.
+fn record_skipped_proposal(proposal: &ProposalData, reason: SkipReason) {
+    let proposal_id = proposal.id.unwrap();
+    print(format!(
+        "Skipping proposal id {}. Reason: {:?}",
+        proposal_id.id, reason
+    ));
+    SKIPPED_PROPOSALS.with(|proposals| {
+        let mut proposals = proposals.borrow_mut();
+        if proposals.iter().any(|skipped| skipped.id == proposal_id) {
+            return;
+        }
+        proposals.push(ProxyProposalQuery {
+            id: proposal_id,
+            action: proposal.action,
+            creation_timestamp: proposal.proposal_creation_timestamp_seconds,
+            participation_status: ParticipationStatus::Skipped { reason },
+            timer_scheduled_for: None,
+            policy: None,
+            council_epoch: None,
+            evaluation: None,
+            title: proposal_title(proposal),
+            url: proposal_url(proposal),
         });
+    });
+}
 
-        *before_proposal = proposal.id;
-        Ok(false)
+fn proposal_title(proposal: &ProposalData) -> Option<String> {
+    proposal
+        .proposal
+        .as_ref()
+        .map(|content| content.title.clone())
+}
+
+fn proposal_url(proposal: &ProposalData) -> Option<String> {
+    proposal
+        .proposal
+        .as_ref()
+        .map(|content| content.url.clone())
+}
+
+/// Refreshes the functions registered in the SNS, flagging the generic functions registered since the
+/// previous scan. If governance cannot list its functions, the scan goes on with the functions known before.
+async fn refresh_functions_for_scan() {
+    if let Err(err) = refresh_registered_functions().await {
+        print(format!(
+            "Listing the registered functions failed, using the known ones. Returned error is: {:#?}",
+            err
+        ));
     }
 }
 
+/// Stops watching proposals whose action is not permitted by the current exclusion settings.
+pub fn purge_excluded_proposals() {
+    WATCHING_PROPOSALS.with(|proposals| {
+        let mut proposals_mutable = proposals.borrow_mut();
+        proposals_mutable.iter().for_each(|proposal| {
+            if !is_action_permitted(proposal.action) && proposal.timer_id.is_some() {
+                // cancel its timer
+                clear_timer(proposal.timer_id.unwrap());
+            }
+        });
+        proposals_mutable.retain(|proposal| is_action_permitted(proposal.action));
+    });
+}
+
 pub async fn vote_on_proposal(
-    id: ProposalId,
-    action: u64,
-    creation_timestamp: u64,
+    proposal: ProxyProposalQuery,
 ) -> Result<ParticipationStatus, CanisterError> {
+    let id = proposal.id;
     if !get_watch_lock() {
         // lock is off.
         return Err(CanisterError::WatchingIsAlreadyStopped);
@@ -202,6 +351,414 @@ pub async fn vote_on_proposal(
 
     change_proposal_lock(id, true)?;
 
+    let data = fetch_proposal(id).await?;
+    let mut participation_status = ParticipationStatus::VotedAgainst;
+
+    if data.reward_event_end_timestamp_seconds.is_some() {
+        // proposal is not accepting votes anymore.
+        // remove this proposal from the watchlist
+        WATCHING_PROPOSALS
+            .with(|proposals| proposals.borrow_mut().retain(|proposal| proposal.id != id));
+        participation_status = ParticipationStatus::TooLateToParticipate;
+        // add this proposal and the final decision of the canister to the history
+        record_outcome(
+            ProxyProposalQuery {
+                participation_status: participation_status.clone(),
+                timer_scheduled_for: None,
+                ..proposal
+            },
+            None,
+        );
+        return Ok(participation_status);
+    }
+
+    let voting_policy = get_proposal_voting_policy(&proposal)?;
+    let (council_epoch, council_members) = get_evaluation_council(proposal.council_epoch);
+    let Evaluation {
+        decision,
+        record: mut evaluation,
+        policy: applied_policy,
+        ..
+    } = evaluate(
+        &proposal,
+        &data.ballots,
+        &voting_policy,
+        &council_members,
+        &get_active_delegations(time() / 1_000_000_000),
+    );
+    if !evaluation.ineligible_members.is_empty() {
+        print(format!(
+            "Council neurons without a ballot on proposal id {} are not counted: {:?}",
+            id.id,
+            evaluation
+                .ineligible_members
+                .iter()
+                .map(|member| member.name.as_str())
+                .collect::<Vec<_>>()
+        ));
+    }
+    if !evaluation.vetoed_by.is_empty() {
+        print(format!(
+            "Proposal id {} is vetoed by: {:?}",
+            id.id,
+            evaluation
+                .vetoed_by
+                .iter()
+                .map(|member| member.name.as_str())
+                .collect::<Vec<_>>()
+        ));
+    }
+
+    let Some(decision) = decision else {
+        // none of the mirrored neurons voted
+        print(format!(
+            "Abstaining from proposal id {}: none of the mirrored neurons voted.",
+            id.id
+        ));
+        WATCHING_PROPOSALS
+            .with(|proposals| proposals.borrow_mut().retain(|proposal| proposal.id != id));
+        record_outcome(
+            ProxyProposalQuery {
+                participation_status: ParticipationStatus::Abstained,
+                timer_scheduled_for: None,
+                policy: None,
+                council_epoch: Some(council_epoch),
+                evaluation: Some(evaluation),
+                ..proposal
+            },
+            None,
+        );
+        return Ok(ParticipationStatus::Abstained);
+    };
+    evaluation.vote_sent = Some(if decision == Vote::Yes {
+        BallotChoice::Yes
+    } else {
+        BallotChoice::No
+    });
+
+    if let Err(err) = vote(id, decision as i32).await {
+        // keep the evaluation on the watched proposal, in case this was the last attempt
+        evaluation.governance_response = Some(VoteResponse::Failed(format!("{:?}", err)));
+        WATCHING_PROPOSALS.with(|proposals| {
+            if let Some(watched) = proposals
+                .borrow_mut()
+                .iter_mut()
+                .find(|watched| watched.id == id)
+            {
+                watched.evaluation = Some(evaluation);
+            }
+        });
+        return Err(err);
+    }
+    evaluation.governance_response = Some(VoteResponse::Registered);
+    record_member_activity(&evaluation.member_ballots);
+    if decision == Vote::Yes {
+        participation_status = ParticipationStatus::VotedFor;
+    }
+
+    // remove this proposal from the watchlist
+    WATCHING_PROPOSALS
+        .with(|proposals| proposals.borrow_mut().retain(|proposal| proposal.id != id));
+
+    // add this proposal and the final decision of the canister to the history
+    record_outcome(
+        ProxyProposalQuery {
+            participation_status: participation_status.clone(),
+            timer_scheduled_for: None,
+            policy: applied_policy.as_ref().map(|policy| policy.name.clone()),
+            council_epoch: Some(council_epoch),
+            evaluation: Some(evaluation),
+            ..proposal
+        },
+        applied_policy,
+    );
+
+    Ok(participation_status)
+}
+
+/// Adds a proposal to the history with its final participation status, and appends the outcome to the
+/// decision log with the policy the tally was decided with, if any. A proposal already in the history is
+/// not recorded again, e.g. when the scanner finds a force-abstained proposal a second time.
+fn record_outcome(proposal: ProxyProposalQuery, policy: Option<VotingPolicy>) {
+    let recorded = PROPOSAL_HISTORY.with(|history| {
+        history
+            .borrow()
+            .iter()
+            .any(|recorded| recorded.id == proposal.id)
+    });
+    if recorded {
+        return;
+    }
+
+    record_decision(DecisionRecord {
+        proposal_id: proposal.id.id,
+        action: proposal.action,
+        council_epoch: proposal.council_epoch,
+        tally: proposal.evaluation.clone(),
+        policy,
+        vote: proposal.participation_status.clone(),
+        decided_at_seconds: time() / 1_000_000_000,
+    });
+    PROPOSAL_HISTORY.with(|history| history.borrow_mut().push(proposal));
+}
+
+/// Shortest interval between two rechecks of the watched proposals.
+pub const MIN_EARLY_DECISION_INTERVAL_SECONDS: u64 = 60;
+
+/// Turns early decisions on with the given recheck interval, or off if not set.
+pub fn set_early_decision_interval(interval_seconds: Option<u64>) -> Result<(), CanisterError> {
+    if let Some(interval) = interval_seconds {
+        if interval < MIN_EARLY_DECISION_INTERVAL_SECONDS {
+            return Err(CanisterError::InvalidEarlyDecisionInterval(interval));
+        }
+    }
+    EARLY_DECISION_INTERVAL_SECONDS.with(|interval| interval.set(interval_seconds));
+    schedule_early_decisions();
+    Ok(())
+}
+
+/// Sets the recurring timer rechecking the watched proposals, replacing any previous one.
+pub fn schedule_early_decisions() {
+    if let Some(timer_id) = EARLY_DECISION_TIMER_ID.with(|id| id.borrow_mut().take()) {
+        clear_timer(timer_id);
+    }
+    let Some(interval) = get_early_decision_interval() else {
+        return;
+    };
+    let timer_id = set_timer_interval(Duration::from_secs(interval), || {
+        spawn(decide_final_proposals())
+    });
+    EARLY_DECISION_TIMER_ID.with(|id| *id.borrow_mut() = Some(timer_id));
+}
+
+/// Votes on the watched proposals whose outcome can no longer change, cancelling their evaluation timer once
+/// the vote succeeded. If the vote fails, the proposal stays watched and its evaluation timer is set again.
+/// Proposals are not decided early with the live council source, since the council itself may still change.
+async fn decide_final_proposals() {
+    if !get_watch_lock() || get_council_source() == CouncilSource::Live {
+        return;
+    }
+
+    for proposal in get_proposal_watchlist() {
+        match is_outcome_final(&proposal).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                print(format!(
+                    "Early decision check failed for proposal id {}. Returned error is: {:#?}",
+                    proposal.id.id, err
+                ));
+                continue;
+            }
+        }
+
+        print(format!(
+            "The council outcome of proposal id {} is final. Voting early.",
+            proposal.id.id
+        ));
+        let id = proposal.id;
+        let deadline = proposal.timer_scheduled_for;
+        let timer_id = WATCHING_PROPOSALS.with(|proposals| {
+            proposals
+                .borrow()
+                .iter()
+                .find(|watched| watched.id == id)
+                .and_then(|watched| watched.timer_id)
+        });
+        match vote_on_proposal(proposal.clone()).await {
+            Ok(_) => {
+                if let Some(timer_id) = timer_id {
+                    clear_timer(timer_id);
+                }
+            }
+            Err(err @ CanisterError::ProposalLocked(_)) => {
+                // the evaluation timer is voting on this proposal right now
+                print(format!(
+                    "Proposal id {} is already being evaluated. Returned error is: {:#?}",
+                    id.id, err
+                ));
+            }
+            Err(err) => {
+                // the next recheck tries again, and the evaluation timer still votes at the deadline
+                let _ = change_proposal_lock(id, false);
+                if let Some(deadline) = deadline {
+                    reschedule_evaluation(proposal, deadline);
+                }
+                print(format!(
+                    "Early voting failed for proposal id {}. Returned error is: {:#?}",
+                    id.id, err
+                ));
+            }
+        }
+    }
+}
+
+/// Replaces the evaluation timer of a watched proposal, which may have fired while an early vote held its lock.
+fn reschedule_evaluation(proposal: ProxyProposalQuery, deadline: u64) {
+    WATCHING_PROPOSALS.with(|proposals| {
+        if let Some(watched) = proposals
+            .borrow_mut()
+            .iter_mut()
+            .find(|watched| watched.id == proposal.id)
+        {
+            if let Some(timer_id) = watched.timer_id.take() {
+                clear_timer(timer_id);
+            }
+            watched.timer_id = Some(schedule_evaluation(proposal, deadline));
+        }
+    });
+}
+
+/// Returns true if the proposal is still open and no ballot the council can still cast changes the proxy's vote.
+async fn is_outcome_final(proposal: &ProxyProposalQuery) -> Result<bool, CanisterError> {
+    let data = fetch_proposal(proposal.id).await?;
+    if data.reward_event_end_timestamp_seconds.is_some() {
+        return Ok(false);
+    }
+    let voting_policy = get_proposal_voting_policy(proposal)?;
+    let (_, council_members) = get_evaluation_council(proposal.council_epoch);
+    // delegations ending before the evaluation may not fill a vote then
+    let evaluation_time = proposal
+        .timer_scheduled_for
+        .unwrap_or_else(|| evaluation_deadline(&data));
+    let delegations: Vec<Delegation> = get_active_delegations(time() / 1_000_000_000)
+        .into_iter()
+        .filter(|delegation| delegation.ends_at > evaluation_time)
+        .collect();
+    let evaluation = evaluate(
+        proposal,
+        &data.ballots,
+        &voting_policy,
+        &council_members,
+        &delegations,
+    );
+    Ok(evaluation.is_final)
+}
+
+/// Counts the current ballots of a watched proposal with its voting policy, without voting.
+pub async fn get_live_tally(id: ProposalId) -> Result<LiveTally, CanisterError> {
+    let proposal = get_proposal_watchlist()
+        .into_iter()
+        .find(|proposal| proposal.id == id)
+        .ok_or(CanisterError::ProposalIsNotInWatchlist(id.id))?;
+
+    let data = fetch_proposal(id).await?;
+    let voting_policy = get_proposal_voting_policy(&proposal)?;
+    let (council_epoch, council_members) = get_evaluation_council(proposal.council_epoch);
+    let Evaluation {
+        decision,
+        record: mut evaluation,
+        ..
+    } = evaluate(
+        &proposal,
+        &data.ballots,
+        &voting_policy,
+        &council_members,
+        &get_active_delegations(time() / 1_000_000_000),
+    );
+    let projected_vote = match decision {
+        Some(Vote::Yes) => BallotChoice::Yes,
+        Some(_) => BallotChoice::No,
+        None => BallotChoice::NotVoted,
+    };
+    let pending_members = council_members
+        .into_iter()
+        .filter(|member| {
+            evaluation.member_ballots.iter().any(|ballot| {
+                ballot.neuron_id == member.neuron_id && ballot.vote == BallotChoice::NotVoted
+            })
+        })
+        .collect();
+
+    Ok(LiveTally {
+        proposal_id: id.id,
+        council_epoch,
+        policy: voting_policy.name,
+        tally: evaluation,
+        projected_vote,
+        pending_members,
+        seconds_until_evaluation: proposal
+            .timer_scheduled_for
+            .map(|deadline| deadline.saturating_sub(time() / 1_000_000_000)),
+    })
+}
+
+/// The proxy's vote on a proposal given its current ballots.
+struct Evaluation {
+    /// The vote to cast, or `None` to abstain.
+    decision: Option<Vote>,
+    /// Whether the decision stays the same however the ballots that can still be cast go.
+    is_final: bool,
+    record: EvaluationRecord,
+    /// The voting policy the decision was taken with. Not set in mirror mode, where no policy applies.
+    policy: Option<VotingPolicy>,
+}
+
+/// Decides the proxy's vote on a proposal in the current voting mode: counts the ballots of `council_members`
+/// with `voting_policy`, or copies the vote of the mirrored neurons. Veto rights apply in both modes.
+fn evaluate(
+    proposal: &ProxyProposalQuery,
+    ballots: &BTreeMap<String, Ballot>,
+    voting_policy: &VotingPolicy,
+    council_members: &[CouncilMember],
+    delegations: &[Delegation],
+) -> Evaluation {
+    let veto_holders = get_veto_holders(proposal.action);
+    if let VotingMode::Mirror(neuron_ids) = get_voting_mode() {
+        let tally = tally_mirror(&neuron_ids, ballots);
+        // only the vetoes are taken from the council's ballots
+        let council = tally_council(
+            council_members,
+            ballots,
+            get_neuron_combine_rule(),
+            &[],
+            delegations,
+            &veto_holders,
+        );
+        let vetoed = !council.vetoed_by.is_empty();
+        let is_final = if council.vetoed_by.len() as u64 > council.provisional_vetoes {
+            true
+        } else {
+            !tally.provisional && !vetoed && council.pending_vetoes == 0
+        };
+        let decision = if vetoed {
+            Some(Vote::No)
+        } else {
+            tally.lead.as_ref().map(|(_, vote)| *vote)
+        };
+        let mut record: EvaluationRecord = tally.into();
+        record.vetoed_by = council.vetoed_by;
+        return Evaluation {
+            decision,
+            is_final,
+            record,
+            policy: None,
+        };
+    }
+
+    let tally = tally_council(
+        council_members,
+        ballots,
+        get_neuron_combine_rule(),
+        &get_suspended_neuron_ids(),
+        delegations,
+        &veto_holders,
+    );
+    let decision = voting_policy.decide(&tally);
+    let is_final = voting_policy.is_final(&tally, get_inactivity_rule().is_some());
+    let mut record: EvaluationRecord = tally.into();
+    record.quorum_percent = Some(voting_policy.quorum_percent);
+    record.approval_percent = Some(voting_policy.approval_percent);
+    Evaluation {
+        decision: Some(decision),
+        is_final,
+        record,
+        policy: Some(voting_policy.clone()),
+    }
+}
+
+/// Fetches the current state of a proposal, including its ballots, from governance.
+async fn fetch_proposal(id: ProposalId) -> Result<ProposalData, CanisterError> {
     let governance_canister_id = get_governance_canister_id()?;
 
     let get_proposal_arg = GetProposal {
@@ -214,89 +771,64 @@ pub async fn vote_on_proposal(
     let get_proposal_handled =
         handle_intercanister_call::<GetProposalResponse>(get_proposal_response)?;
 
-    if get_proposal_handled.result.is_none() {
-        return Err(CanisterError::Unknown(format!(
+    match get_proposal_handled.result {
+        None => Err(CanisterError::Unknown(format!(
             "Proposal data could not be found. Id: {:#?}",
             id
-        )));
+        ))),
+        Some(get_proposal_response::Result::Error(err)) => Err(CanisterError::Unknown(format!(
+            "Governance error on proposal data: {}",
+            err.error_message
+        ))),
+        Some(get_proposal_response::Result::Proposal(data)) => Ok(data),
     }
+}
 
-    let proposal_result = get_proposal_handled.result.unwrap();
-    let mut participation_status = ParticipationStatus::VotedAgainst;
-
-    match proposal_result {
-        ic_sns_governance::pb::v1::get_proposal_response::Result::Error(err) => {
-            Err(CanisterError::Unknown(format!(
-                "Governance error on proposal data: {}",
-                err.error_message
-            )))
+/// Returns the voting policy assigned to the proposal, or the default one if it no longer exists.
+fn get_proposal_voting_policy(
+    proposal: &ProxyProposalQuery,
+) -> Result<VotingPolicy, CanisterError> {
+    match get_voting_policy(proposal.policy.as_deref()) {
+        Ok(voting_policy) => Ok(voting_policy),
+        Err(_) => {
+            print(format!(
+                "Voting policy {:?} of proposal id {} no longer exists. Using the default policy.",
+                proposal.policy, proposal.id.id
+            ));
+            get_voting_policy(None)
         }
-        ic_sns_governance::pb::v1::get_proposal_response::Result::Proposal(data) => {
-            if data.reward_event_end_timestamp_seconds.is_some() {
-                // proposal is not accepting votes anymore.
-                // remove this proposal from the watchlist
-                WATCHING_PROPOSALS
-                    .with(|proposals| proposals.borrow_mut().retain(|proposal| proposal.id != id));
-                participation_status = ParticipationStatus::TooLateToParticipate;
-                // add this proposal and the final decision of the canister to the history
-                PROPOSAL_HISTORY.with(|proposals| {
-                    proposals.borrow_mut().push(ProxyProposalQuery {
-                        id,
-                        action,
-                        creation_timestamp,
-                        participation_status: participation_status.clone(),
-                        timer_scheduled_for: None,
-                    });
-                });
-                return Ok(participation_status);
-            }
-
-            let council_members = get_council_members();
-            let ballots = data.ballots;
-            let mut decision: i32 = 0;
-            let mut voters_count: u64 = 0;
-
-            let voting_threshold: u64 = council_members.len() as u64 / 2;
+    }
+}
 
-            council_members.into_iter().for_each(|member| {
-                let ballot = ballots.get(&member.neuron_id);
-                if let Some(vote) = ballot {
-                    // council member has voted.
-                    decision += vote.vote;
-                    voters_count += 1;
-                }
-            });
+/// Stops watching new proposals and cancels the timers of the watched ones.
+pub fn stop_watching() -> Result<(), CanisterError> {
+    if !get_watch_lock() {
+        // lock is off.
+        return Err(CanisterError::WatchingIsAlreadyStopped);
+    }
 
-            if voters_count > voting_threshold {
-                // more than 50% of council members have voted. Participate.
-                if decision > 0 {
-                    // vote yes
-                    vote(id, 1).await?;
-                    participation_status = ParticipationStatus::VotedFor;
-                } else if decision < 0 || decision == 0 {
-                    // vote no
-                    vote(id, 0).await?;
-                }
-            } else {
-                vote(id, 0).await?;
+    // Cancel all timers
+    WATCHING_PROPOSALS.with(|proposals| {
+        let mut proposals = proposals.borrow_mut();
+        for proposal in proposals.iter_mut() {
+            proposal.lock = true;
+            if let Some(timer_id) = proposal.timer_id {
+                clear_timer(timer_id);
             }
+            proposal.lock = false;
+        }
+        *proposals = vec![];
+    });
 
-            // remove this proposal from the watchlist
-            WATCHING_PROPOSALS
-                .with(|proposals| proposals.borrow_mut().retain(|proposal| proposal.id != id));
-
-            // add this proposal and the final decision of the canister to the history
-            PROPOSAL_HISTORY.with(|proposals| {
-                proposals.borrow_mut().push(ProxyProposalQuery {
-                    id,
-                    action,
-                    creation_timestamp,
-                    participation_status: participation_status.clone(),
-                    timer_scheduled_for: None,
-                });
-            });
+    let fetcher_timer_id = get_fetcher_timer_id();
 
-            Ok(participation_status)
-        }
+    if fetcher_timer_id.is_some() {
+        clear_timer(fetcher_timer_id.unwrap());
     }
+
+    FETCHER_TIMER_ID.with(|id| *id.borrow_mut() = None);
+
+    WATCH_LOCK.with(|lock| lock.set(false));
+
+    Ok(())
 }
@@ -1,13 +1,25 @@
 use std::cell::{Cell, RefCell};
 
-use ic_exports::{ic_cdk_timers::TimerId, ic_kit::Principal};
+use ic_exports::{
+    candid::CandidType, ic_cdk::api::is_controller, ic_cdk_timers::TimerId, ic_kit::Principal,
+};
 use ic_sns_governance::pb::v1::{NeuronId, ProposalId};
+use serde::Deserialize;
 
 use crate::{
-    types::{CanisterError, CouncilMember, ProxyProposal, ProxyProposalQuery},
+    types::{
+        AuditEntry, CanisterError, CouncilEpoch, CouncilMember, CouncilSource, DecisionEntry,
+        Delegation, ExclusionMode, FilterAction, FilterMatcher, FilterRule, FlaggedActionHandling,
+        InactivityRule, MemberActivity, MultisigPolicy, NeuronCombineRule, PendingAction,
+        ProxyProposal, ProxyProposalQuery, QueuedChange, RegisteredFunction, Role, RoleAssignment,
+        SuspensionEvent, VetoRight, VotingMode, VotingPolicy,
+    },
     utils::not_anonymous,
 };
 
+/// Name of the voting policy that applies when no filter rule assigns another one.
+pub const DEFAULT_VOTING_POLICY: &str = "default";
+
 thread_local! {
     /// Watching status for new proposals
     pub static WATCH_LOCK: Cell<bool> = Cell::new(false);
@@ -21,16 +33,133 @@ thread_local! {
     pub static MAX_RETRIES: Cell<u8> = Cell::new(3);
     /// Vector of all current council members
     pub static COUNCIL_MEMBERS: RefCell<Vec<CouncilMember>> = RefCell::new(Vec::new());
+    /// Appointed council members whose term has not started yet. They join the council when it starts.
+    pub static UPCOMING_COUNCIL_MEMBERS: RefCell<Vec<CouncilMember>> = RefCell::new(Vec::new());
+    /// Timer applying the next start or end of a council member's term.
+    pub static COUNCIL_TERM_TIMER_ID: RefCell<Option<TimerId>> = RefCell::new(None);
+    /// Every version of the council, oldest first. Epoch 0 is the empty council of a fresh install and is not recorded.
+    pub static COUNCIL_EPOCHS: RefCell<Vec<CouncilEpoch>> = RefCell::new(Vec::new());
+    /// Whether proposals are evaluated against the council at discovery time or the live council.
+    pub static COUNCIL_SOURCE: Cell<CouncilSource> = Cell::new(CouncilSource::Snapshot);
+    /// Whether the proxy counts the council's ballots or copies the vote of a lead neuron.
+    pub static VOTING_MODE: RefCell<VotingMode> = RefCell::new(VotingMode::Council);
+    /// How the ballots of a council member's neurons are combined into the member's vote.
+    pub static NEURON_COMBINE_RULE: Cell<NeuronCombineRule> = Cell::new(NeuronCombineRule::FirstVote);
+    /// How often watched proposals are rechecked for an outcome that can no longer change. Early decisions are off if not set.
+    pub static EARLY_DECISION_INTERVAL_SECONDS: Cell<Option<u64>> = Cell::new(None);
+    /// Recurring timer rechecking the watched proposals for early decisions.
+    pub static EARLY_DECISION_TIMER_ID: RefCell<Option<TimerId>> = RefCell::new(None);
+    /// When members that stopped voting are suspended. Members are never suspended if not set.
+    pub static INACTIVITY_RULE: Cell<Option<InactivityRule>> = Cell::new(None);
+    /// Participation of the council members in the evaluated proposals, by primary neuron.
+    pub static MEMBER_ACTIVITY: RefCell<Vec<MemberActivity>> = RefCell::new(Vec::new());
+    /// Every suspension and reinstatement of a council member, oldest first.
+    pub static SUSPENSION_LOG: RefCell<Vec<SuspensionEvent>> = RefCell::new(Vec::new());
+    /// Council members voting on behalf of others, at most one delegation per delegator.
+    pub static DELEGATIONS: RefCell<Vec<Delegation>> = RefCell::new(Vec::new());
+    /// Council members who can block proposals of some action types, one entry per member.
+    pub static VETO_RIGHTS: RefCell<Vec<VetoRight>> = RefCell::new(Vec::new());
     /// Proposals that are currently being watched (a one-off timer will be triggered one hour before the voting deadline)
     pub static WATCHING_PROPOSALS: RefCell<Vec<ProxyProposal>> = RefCell::new(Vec::new());
     /// Proposals that had been watched.
     pub static PROPOSAL_HISTORY: RefCell<Vec<ProxyProposalQuery>> = RefCell::new(Vec::new());
+    /// Proposals that the scanner found but did not watch, with the reason in their participation status.
+    pub static SKIPPED_PROPOSALS: RefCell<Vec<ProxyProposalQuery>> = RefCell::new(Vec::new());
     /// Actions that will be ignored (the proxy canister won't vote on proposals that have an action from this list)
     pub static EXCLUDED_ACTION_IDS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+    /// Whether the excluded or the allowed action IDs decide which proposals are handled.
+    pub static EXCLUSION_MODE: Cell<ExclusionMode> = Cell::new(ExclusionMode::Denylist);
+    /// Actions that will be handled in allowlist mode. Proposals with any other action are ignored.
+    pub static ALLOWED_ACTION_IDS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+    /// Functions registered in the SNS, as of the last `list_nervous_system_functions` call.
+    pub static REGISTERED_FUNCTIONS: RefCell<Vec<RegisteredFunction>> = RefCell::new(Vec::new());
+    /// Generic functions registered after the proxy started tracking functions, awaiting review by an operator.
+    pub static FLAGGED_ACTION_IDS: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+    /// Whether proposals with a flagged action are handled.
+    pub static FLAGGED_ACTION_HANDLING: Cell<FlaggedActionHandling> = Cell::new(FlaggedActionHandling::FollowExclusionMode);
     /// The last proposal that was handled in this canister.
     pub static LAST_PROPOSAL: RefCell<Option<ProxyProposalQuery>> = RefCell::new(None);
+    /// Roles granted to principals, in addition to the admin role every controller has.
+    pub static ROLE_ASSIGNMENTS: RefCell<Vec<RoleAssignment>> = RefCell::new(Vec::new());
+    /// Signers that must approve sensitive operations. Sensitive operations execute on a single admin call if not set.
+    pub static MULTISIG_POLICY: RefCell<Option<MultisigPolicy>> = RefCell::new(None);
+    /// Sensitive operations waiting for approval, oldest first.
+    pub static PENDING_ACTIONS: RefCell<Vec<PendingAction>> = RefCell::new(Vec::new());
+    /// ID of the next submitted sensitive operation.
+    pub static NEXT_PENDING_ACTION_ID: Cell<u64> = Cell::new(0);
+    /// How long security-relevant configuration changes wait before they take effect. They take effect immediately if 0.
+    pub static TIMELOCK_DELAY_SECONDS: Cell<u64> = Cell::new(0);
+    /// Configuration changes waiting for their activation time, oldest first.
+    pub static QUEUED_CHANGES: RefCell<Vec<QueuedChange>> = RefCell::new(Vec::new());
+    /// ID of the next queued configuration change.
+    pub static NEXT_QUEUED_CHANGE_ID: Cell<u64> = Cell::new(0);
+    /// Timer applying the next queued configuration change.
+    pub static TIMELOCK_TIMER_ID: RefCell<Option<TimerId>> = RefCell::new(None);
+    /// Every call to a mutating endpoint, oldest first. No endpoint removes entries.
+    pub static AUDIT_LOG: RefCell<Vec<AuditEntry>> = RefCell::new(Vec::new());
+    /// The final outcome of every proposal the proxy handled, oldest first. Each entry hashes the previous one.
+    pub static DECISION_LOG: RefCell<Vec<DecisionEntry>> = RefCell::new(Vec::new());
     /// The proxy canister's neuron ID.
     pub static NEURON_ID: RefCell<Option<NeuronId>> = RefCell::new(None);
+    /// Rules deciding which proposals are skipped, abstained on or evaluated with a specific policy. The first matching rule applies.
+    pub static FILTER_RULES: RefCell<Vec<FilterRule>> = RefCell::new(default_filter_rules());
+    /// ID of the next added filter rule. IDs of removed rules are not reused.
+    pub static NEXT_FILTER_RULE_ID: Cell<u64> = Cell::new(1);
+    /// Named voting policies. The default policy is always present.
+    pub static VOTING_POLICIES: RefCell<Vec<VotingPolicy>> = RefCell::new(default_voting_policies());
+}
+
+/// State introduced after the first release. Saved next to the original state on upgrades.
+/// Every field is optional so that stable memory written by older versions can still be restored.
+#[derive(CandidType, Deserialize, Default)]
+pub struct StableStateExtension {
+    pub filter_rules: Option<Vec<FilterRule>>,
+    pub next_filter_rule_id: Option<u64>,
+    pub voting_policies: Option<Vec<VotingPolicy>>,
+    pub exclusion_mode: Option<ExclusionMode>,
+    pub allowed_action_ids: Option<Vec<u64>>,
+    pub registered_functions: Option<Vec<RegisteredFunction>>,
+    pub flagged_action_ids: Option<Vec<u64>>,
+    pub flagged_action_handling: Option<FlaggedActionHandling>,
+    pub skipped_proposals: Option<Vec<ProxyProposalQuery>>,
+    pub council_epochs: Option<Vec<CouncilEpoch>>,
+    pub council_source: Option<CouncilSource>,
+    pub neuron_combine_rule: Option<NeuronCombineRule>,
+    pub upcoming_council_members: Option<Vec<CouncilMember>>,
+    pub role_assignments: Option<Vec<RoleAssignment>>,
+    pub multisig_policy: Option<MultisigPolicy>,
+    pub pending_actions: Option<Vec<PendingAction>>,
+    pub next_pending_action_id: Option<u64>,
+    pub timelock_delay_seconds: Option<u64>,
+    pub queued_changes: Option<Vec<QueuedChange>>,
+    pub next_queued_change_id: Option<u64>,
+    pub audit_log: Option<Vec<AuditEntry>>,
+    pub decision_log: Option<Vec<DecisionEntry>>,
+    pub early_decision_interval_seconds: Option<u64>,
+    pub inactivity_rule: Option<InactivityRule>,
+    pub member_activity: Option<Vec<MemberActivity>>,
+    pub suspension_log: Option<Vec<SuspensionEvent>>,
+    pub delegations: Option<Vec<Delegation>>,
+    pub veto_rights: Option<Vec<VetoRight>>,
+    pub voting_mode: Option<VotingMode>,
+}
+
+/// Proposals configuring the council neuron itself are skipped unless the rule is removed.
+pub fn default_filter_rules() -> Vec<FilterRule> {
+    vec![FilterRule {
+        id: 0,
+        matcher: FilterMatcher::TitlePrefix("CONFIGURE COUNCIL NEURON".to_string()),
+        action: FilterAction::Skip,
+    }]
+}
+
+/// The proxy follows the council once more than half of it voted, and votes yes on a simple majority.
+pub fn default_voting_policies() -> Vec<VotingPolicy> {
+    vec![VotingPolicy {
+        name: DEFAULT_VOTING_POLICY.to_string(),
+        quorum_percent: 50,
+        approval_percent: 50,
+    }]
 }
 
 /// Attempts to change the proposal's lock status with `lock`
@@ -82,6 +211,39 @@ pub fn get_exclusion_list() -> Vec<u64> {
     EXCLUDED_ACTION_IDS.with(|actions| actions.borrow().clone())
 }
 
+pub fn get_exclusion_mode() -> ExclusionMode {
+    EXCLUSION_MODE.with(|mode| mode.get())
+}
+
+pub fn get_allowed_action_types() -> Vec<u64> {
+    ALLOWED_ACTION_IDS.with(|actions| actions.borrow().clone())
+}
+
+pub fn get_registered_functions() -> Vec<RegisteredFunction> {
+    REGISTERED_FUNCTIONS.with(|functions| functions.borrow().clone())
+}
+
+pub fn get_flagged_action_types() -> Vec<u64> {
+    FLAGGED_ACTION_IDS.with(|actions| actions.borrow().clone())
+}
+
+pub fn get_flagged_action_handling() -> FlaggedActionHandling {
+    FLAGGED_ACTION_HANDLING.with(|handling| handling.get())
+}
+
+/// Returns true if proposals with this action are handled under the current exclusion settings.
+pub fn is_action_permitted(action: u64) -> bool {
+    if get_flagged_action_handling() == FlaggedActionHandling::Ignore
+        && get_flagged_action_types().contains(&action)
+    {
+        return false;
+    }
+    match get_exclusion_mode() {
+        ExclusionMode::Denylist => !get_exclusion_list().contains(&action),
+        ExclusionMode::Allowlist => get_allowed_action_types().contains(&action),
+    }
+}
+
 pub fn get_neuron() -> Result<NeuronId, CanisterError> {
     let neuron_id = NEURON_ID.with(|id| id.borrow().clone());
     if neuron_id.is_some() {
@@ -110,10 +272,136 @@ pub fn get_proposal_history() -> Vec<ProxyProposalQuery> {
     })
 }
 
+pub fn get_filter_rules() -> Vec<FilterRule> {
+    FILTER_RULES.with(|rules| rules.borrow().clone())
+}
+
+pub fn get_voting_policies() -> Vec<VotingPolicy> {
+    VOTING_POLICIES.with(|policies| policies.borrow().clone())
+}
+
+/// Returns the policy with the given name, or the default policy if `name` is not set.
+pub fn get_voting_policy(name: Option<&str>) -> Result<VotingPolicy, CanisterError> {
+    let name = name.unwrap_or(DEFAULT_VOTING_POLICY);
+    VOTING_POLICIES.with(|policies| {
+        policies
+            .borrow()
+            .iter()
+            .find(|policy| policy.name == name)
+            .cloned()
+            .ok_or(CanisterError::VotingPolicyNotFound(name.to_string()))
+    })
+}
+
+pub fn get_skipped_proposals() -> Vec<ProxyProposalQuery> {
+    SKIPPED_PROPOSALS.with(|proposals| proposals.borrow().clone())
+}
+
 pub fn get_council_members() -> Vec<CouncilMember> {
     COUNCIL_MEMBERS.with(|members| members.borrow().clone())
 }
 
+pub fn get_upcoming_council_members() -> Vec<CouncilMember> {
+    UPCOMING_COUNCIL_MEMBERS.with(|members| members.borrow().clone())
+}
+
+/// Returns the council members followed by the appointed members whose term has not started yet.
+pub fn get_appointed_council_members() -> Vec<CouncilMember> {
+    let mut members = get_council_members();
+    members.extend(get_upcoming_council_members());
+    members
+}
+
+pub fn get_council_epochs() -> Vec<CouncilEpoch> {
+    COUNCIL_EPOCHS.with(|epochs| epochs.borrow().clone())
+}
+
+/// Returns the number of the current council epoch.
+pub fn get_current_council_epoch() -> u64 {
+    COUNCIL_EPOCHS.with(|epochs| epochs.borrow().last().map_or(0, |epoch| epoch.epoch))
+}
+
+pub fn get_council_source() -> CouncilSource {
+    COUNCIL_SOURCE.with(|source| source.get())
+}
+
+pub fn get_voting_mode() -> VotingMode {
+    VOTING_MODE.with(|mode| mode.borrow().clone())
+}
+
+pub fn get_neuron_combine_rule() -> NeuronCombineRule {
+    NEURON_COMBINE_RULE.with(|rule| rule.get())
+}
+
+pub fn get_role_assignments() -> Vec<RoleAssignment> {
+    ROLE_ASSIGNMENTS.with(|assignments| assignments.borrow().clone())
+}
+
+/// Returns the roles of the principal. Controllers are always admins.
+pub fn get_roles(principal: &Principal) -> Vec<Role> {
+    let mut roles: Vec<Role> = get_role_assignments()
+        .into_iter()
+        .filter(|assignment| &assignment.principal == principal)
+        .map(|assignment| assignment.role)
+        .collect();
+    if is_controller(principal) && !roles.contains(&Role::Admin) {
+        roles.push(Role::Admin);
+    }
+    roles
+}
+
+pub fn get_multisig_policy() -> Option<MultisigPolicy> {
+    MULTISIG_POLICY.with(|policy| policy.borrow().clone())
+}
+
+pub fn get_pending_actions() -> Vec<PendingAction> {
+    PENDING_ACTIONS.with(|actions| actions.borrow().clone())
+}
+
+pub fn get_early_decision_interval() -> Option<u64> {
+    EARLY_DECISION_INTERVAL_SECONDS.with(|interval| interval.get())
+}
+
+pub fn get_inactivity_rule() -> Option<InactivityRule> {
+    INACTIVITY_RULE.with(|rule| rule.get())
+}
+
+pub fn get_member_activity() -> Vec<MemberActivity> {
+    MEMBER_ACTIVITY.with(|activity| activity.borrow().clone())
+}
+
+/// Primary neuron IDs of the suspended council members.
+pub fn get_suspended_neuron_ids() -> Vec<String> {
+    MEMBER_ACTIVITY.with(|activity| {
+        activity
+            .borrow()
+            .iter()
+            .filter(|member| member.suspended_since.is_some())
+            .map(|member| member.neuron_id.clone())
+            .collect()
+    })
+}
+
+pub fn get_suspension_log() -> Vec<SuspensionEvent> {
+    SUSPENSION_LOG.with(|log| log.borrow().clone())
+}
+
+pub fn get_timelock_delay() -> u64 {
+    TIMELOCK_DELAY_SECONDS.with(|delay| delay.get())
+}
+
+pub fn get_queued_changes() -> Vec<QueuedChange> {
+    QUEUED_CHANGES.with(|changes| changes.borrow().clone())
+}
+
+pub fn get_audit_log() -> Vec<AuditEntry> {
+    AUDIT_LOG.with(|log| log.borrow().clone())
+}
+
+pub fn get_decision_log() -> Vec<DecisionEntry> {
+    DECISION_LOG.with(|log| log.borrow().clone())
+}
+
 pub fn get_max_retries() -> u8 {
     MAX_RETRIES.with(|count| count.get())
 }
@@ -0,0 +1,65 @@
+use crate::{
+    state::DECISION_LOG,
+    types::{BallotChoice, MemberStats, ParticipationStatus},
+};
+
+/// Counts the participation of every council member in the votes registered by the proxy between `from_seconds`
+/// and `to_seconds`, both inclusive. Members are identified by their primary neuron and listed by first
+/// appearance, with the name they had in their latest decision. Ballots cast by a member's delegate are only
+/// counted as delegated.
+pub fn get_council_stats(from_seconds: Option<u64>, to_seconds: Option<u64>) -> Vec<MemberStats> {
+    let mut stats: Vec<MemberStats> = vec![];
+
+    DECISION_LOG.with(|log| {
+        for entry in log.borrow().iter() {
+            let record = &entry.record;
+            if from_seconds.map_or(false, |from| record.decided_at_seconds < from)
+                || to_seconds.map_or(false, |to| record.decided_at_seconds > to)
+            {
+                continue;
+            }
+            let proxy_vote = match record.vote {
+                ParticipationStatus::VotedFor => BallotChoice::Yes,
+                ParticipationStatus::VotedAgainst => BallotChoice::No,
+                _ => continue,
+            };
+            let Some(tally) = &record.tally else {
+                continue;
+            };
+
+            for ballot in &tally.member_ballots {
+                let member = match stats
+                    .iter()
+                    .position(|member| member.neuron_id == ballot.neuron_id)
+                {
+                    Some(position) => &mut stats[position],
+                    None => {
+                        stats.push(MemberStats {
+                            neuron_id: ballot.neuron_id.clone(),
+                            ..Default::default()
+                        });
+                        stats.last_mut().unwrap()
+                    }
+                };
+                member.name = ballot.name.clone();
+                member.eligible += 1;
+                if ballot.delegate.is_some() {
+                    // the delegate voted, not the member
+                    member.delegated += 1;
+                    continue;
+                }
+                match ballot.vote {
+                    BallotChoice::Yes => member.yes += 1,
+                    BallotChoice::No => member.no += 1,
+                    BallotChoice::NotVoted => continue,
+                }
+                member.voted += 1;
+                if ballot.vote == proxy_vote {
+                    member.matched_proxy_vote += 1;
+                }
+            }
+        }
+    });
+
+    stats
+}
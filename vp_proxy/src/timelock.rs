@@ -0,0 +1,248 @@
+use std::time::Duration;
+
+use ic_exports::{
+    candid::Principal,
+    ic_cdk::{api::time, print, spawn},
+    ic_cdk_timers::{clear_timer, set_timer},
+};
+
+use crate::{
+    actions::{
+        allow_action_type, disallow_action_type, set_exclusion_mode, set_flagged_action_handling,
+        unflag_action_type,
+    },
+    activity::{reinstate_member, set_inactivity_rule},
+    council::{
+        add_council_member, normalize_council, normalize_council_member, normalize_neuron_id,
+        remove_council_member, set_council, update_council_member,
+    },
+    delegation::{normalize_delegation, remove_delegation, set_delegation},
+    filters::{
+        add_filter_rule, normalize_filter_matcher, remove_filter_rule, validate_filter_action,
+    },
+    mirror::{normalize_voting_mode, set_voting_mode},
+    state::{
+        get_queued_changes, get_timelock_delay, COUNCIL_SOURCE, NEURON_COMBINE_RULE,
+        NEXT_QUEUED_CHANGE_ID, QUEUED_CHANGES, TIMELOCK_DELAY_SECONDS, TIMELOCK_TIMER_ID,
+    },
+    types::{CanisterError, ConfigChange, QueuedChange, Role, VetoRight},
+    utils::only_roles,
+    veto::{remove_veto_right, set_veto_right},
+    voting::{remove_voting_policy, set_voting_policy, validate_voting_policy},
+};
+
+/// Rejects direct configuration changes while a timelock delay is set.
+/// They must be queued with `queue_change` instead.
+///
+/// Every admin and operator setter of the voting configuration is covered, through a `ConfigChange`
+/// variant. The following endpoints are deliberately exempt:
+/// - the `set_my_*` and `remove_my_delegation` endpoints, since members only change their own record;
+/// - `grant_role` and `revoke_role`, since roles only decide who may make changes that are delayed anyway;
+/// - the sensitive operations, e.g. `emergency_reset`, which are guarded by the approval queue instead;
+/// - `set_ledger_id`, `set_early_decision_interval`, `refresh_action_types`, `watch_proposals` and
+///   `clear_proposal_history`, which change when or whether proposals are handled, not how the proxy votes.
+pub fn ensure_no_timelock() -> Result<(), CanisterError> {
+    if get_timelock_delay() > 0 {
+        return Err(CanisterError::TimelockRequired);
+    }
+    Ok(())
+}
+
+/// Changes to the action lists, the flagged actions and the filter rules are open to operators, like the
+/// endpoints making them directly.
+fn only_change_roles(caller: Principal, change: &ConfigChange) -> Result<(), CanisterError> {
+    match change {
+        ConfigChange::AllowActionType(_)
+        | ConfigChange::DisallowActionType(_)
+        | ConfigChange::AcknowledgeActionType(_)
+        | ConfigChange::SetFlaggedActionHandling(_)
+        | ConfigChange::AddFilterRule { .. }
+        | ConfigChange::RemoveFilterRule(_) => only_roles(caller, &[Role::Operator]),
+        _ => only_roles(caller, &[]),
+    }
+}
+
+/// Validates what can be checked without calling governance and returns the change in normalized form.
+fn normalize_change(change: ConfigChange) -> Result<ConfigChange, CanisterError> {
+    Ok(match change {
+        ConfigChange::AddCouncilMember(member) => {
+            ConfigChange::AddCouncilMember(normalize_council_member(member)?)
+        }
+        ConfigChange::UpdateCouncilMember { neuron_id, member } => {
+            ConfigChange::UpdateCouncilMember {
+                neuron_id: normalize_neuron_id(&neuron_id)?,
+                member: normalize_council_member(member)?,
+            }
+        }
+        ConfigChange::RemoveCouncilMember(neuron_id) => {
+            ConfigChange::RemoveCouncilMember(normalize_neuron_id(&neuron_id)?)
+        }
+        ConfigChange::SetCouncil(members) => ConfigChange::SetCouncil(normalize_council(members)?),
+        ConfigChange::SetVotingPolicy(policy) => {
+            validate_voting_policy(&policy)?;
+            ConfigChange::SetVotingPolicy(policy)
+        }
+        ConfigChange::SetVotingMode(mode) => {
+            ConfigChange::SetVotingMode(normalize_voting_mode(mode)?)
+        }
+        ConfigChange::SetDelegation(delegation) => {
+            ConfigChange::SetDelegation(normalize_delegation(delegation)?)
+        }
+        ConfigChange::RemoveDelegation(delegator) => {
+            ConfigChange::RemoveDelegation(normalize_neuron_id(&delegator)?)
+        }
+        ConfigChange::SetVetoRight(right) => ConfigChange::SetVetoRight(VetoRight {
+            neuron_id: normalize_neuron_id(&right.neuron_id)?,
+            ..right
+        }),
+        ConfigChange::RemoveVetoRight(neuron_id) => {
+            ConfigChange::RemoveVetoRight(normalize_neuron_id(&neuron_id)?)
+        }
+        ConfigChange::ReinstateCouncilMember(neuron_id) => {
+            ConfigChange::ReinstateCouncilMember(normalize_neuron_id(&neuron_id)?)
+        }
+        ConfigChange::AddFilterRule { matcher, action } => {
+            validate_filter_action(&action)?;
+            ConfigChange::AddFilterRule {
+                matcher: normalize_filter_matcher(matcher)?,
+                action,
+            }
+        }
+        change => change,
+    })
+}
+
+/// Queues a configuration change to take effect once the timelock delay has passed, and returns its ID.
+/// Without a delay, the change is applied right away.
+pub async fn queue_change(caller: Principal, change: ConfigChange) -> Result<u64, CanisterError> {
+    only_change_roles(caller, &change)?;
+    let change = normalize_change(change)?;
+    let id = NEXT_QUEUED_CHANGE_ID.with(|id| id.replace(id.get() + 1));
+    let delay = get_timelock_delay();
+    if delay == 0 {
+        apply_change(change, caller).await?;
+        return Ok(id);
+    }
+
+    let now = time() / 1_000_000_000;
+    QUEUED_CHANGES.with(|changes| {
+        changes.borrow_mut().push(QueuedChange {
+            id,
+            change,
+            queued_by: caller,
+            queued_at_seconds: now,
+            activates_at_seconds: now.saturating_add(delay),
+        })
+    });
+    schedule_queued_changes();
+    Ok(id)
+}
+
+/// Drops a queued change before it takes effect.
+pub fn cancel_change(caller: Principal, id: u64) -> Result<(), CanisterError> {
+    let change = get_queued_changes()
+        .into_iter()
+        .find(|change| change.id == id)
+        .ok_or(CanisterError::QueuedChangeNotFound(id))?;
+    only_change_roles(caller, &change.change)?;
+    QUEUED_CHANGES.with(|changes| changes.borrow_mut().retain(|change| change.id != id));
+    schedule_queued_changes();
+    Ok(())
+}
+
+/// Sets a timer for the next activation time. Called whenever the queue changes and after upgrades.
+pub fn schedule_queued_changes() {
+    if let Some(timer_id) = TIMELOCK_TIMER_ID.with(|id| id.borrow_mut().take()) {
+        clear_timer(timer_id);
+    }
+
+    let next_activation = get_queued_changes()
+        .iter()
+        .map(|change| change.activates_at_seconds)
+        .min();
+    let Some(next_activation) = next_activation else {
+        return;
+    };
+
+    let delay = next_activation.saturating_sub(time() / 1_000_000_000);
+    let timer_id = set_timer(Duration::from_secs(delay), || spawn(apply_due_changes()));
+    TIMELOCK_TIMER_ID.with(|id| *id.borrow_mut() = Some(timer_id));
+}
+
+/// Applies the changes whose activation time has passed, in the order they were queued.
+/// A change that fails is dropped, e.g. if its neuron no longer exists.
+async fn apply_due_changes() {
+    let now = time() / 1_000_000_000;
+    let due: Vec<QueuedChange> = QUEUED_CHANGES.with(|changes| {
+        let mut changes = changes.borrow_mut();
+        let (due, pending) = changes
+            .drain(..)
+            .partition(|change| change.activates_at_seconds <= now);
+        *changes = pending;
+        due
+    });
+    schedule_queued_changes();
+
+    for queued in due {
+        if let Err(err) = apply_change(queued.change, queued.queued_by).await {
+            print(format!(
+                "Failed to apply queued change {}: {:?}",
+                queued.id, err
+            ));
+        }
+    }
+}
+
+/// Applies a change on behalf of `queued_by`, the principal that queued it.
+async fn apply_change(change: ConfigChange, queued_by: Principal) -> Result<(), CanisterError> {
+    match change {
+        ConfigChange::AddCouncilMember(member) => add_council_member(member).await,
+        ConfigChange::UpdateCouncilMember { neuron_id, member } => {
+            update_council_member(&neuron_id, member).await
+        }
+        ConfigChange::RemoveCouncilMember(neuron_id) => remove_council_member(&neuron_id),
+        ConfigChange::SetCouncil(members) => set_council(members).await.map(|_| ()),
+        ConfigChange::AllowActionType(action_type) => allow_action_type(action_type).await,
+        ConfigChange::DisallowActionType(action_type) => disallow_action_type(action_type).await,
+        ConfigChange::SetExclusionMode(mode) => {
+            set_exclusion_mode(mode);
+            Ok(())
+        }
+        ConfigChange::SetVotingPolicy(policy) => {
+            set_voting_policy(policy);
+            Ok(())
+        }
+        ConfigChange::RemoveVotingPolicy(name) => remove_voting_policy(&name),
+        ConfigChange::SetTimelockDelay(delay) => {
+            TIMELOCK_DELAY_SECONDS.with(|current| current.set(delay));
+            Ok(())
+        }
+        ConfigChange::SetVotingMode(mode) => set_voting_mode(mode).await,
+        ConfigChange::SetCouncilSource(source) => {
+            COUNCIL_SOURCE.with(|council_source| council_source.set(source));
+            Ok(())
+        }
+        ConfigChange::SetNeuronCombineRule(rule) => {
+            NEURON_COMBINE_RULE.with(|combine_rule| combine_rule.set(rule));
+            Ok(())
+        }
+        ConfigChange::SetDelegation(delegation) => set_delegation(delegation),
+        ConfigChange::RemoveDelegation(delegator) => remove_delegation(&delegator),
+        ConfigChange::SetVetoRight(right) => set_veto_right(right),
+        ConfigChange::RemoveVetoRight(neuron_id) => remove_veto_right(&neuron_id),
+        ConfigChange::SetInactivityRule(rule) => set_inactivity_rule(rule),
+        ConfigChange::ReinstateCouncilMember(neuron_id) => reinstate_member(&neuron_id, queued_by),
+        ConfigChange::AddFilterRule { matcher, action } => {
+            add_filter_rule(matcher, action).map(|_| ())
+        }
+        ConfigChange::RemoveFilterRule(id) => remove_filter_rule(id),
+        ConfigChange::SetFlaggedActionHandling(handling) => {
+            set_flagged_action_handling(handling);
+            Ok(())
+        }
+        ConfigChange::AcknowledgeActionType(action_type) => {
+            unflag_action_type(action_type);
+            Ok(())
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use ic_exports::{candid::CandidType, ic_cdk_timers::TimerId};
+use ic_exports::{
+    candid::{CandidType, Nat, Principal},
+    ic_cdk_timers::TimerId,
+};
 use ic_sns_governance::pb::v1::ProposalId;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +12,16 @@ pub struct ProxyProposalQuery {
     pub creation_timestamp: u64,
     pub participation_status: ParticipationStatus,
     pub timer_scheduled_for: Option<u64>,
+    /// Name of the voting policy a filter rule assigned to this proposal. The default policy applies if not set.
+    pub policy: Option<String>,
+    /// Council epoch the proposal is evaluated against: the epoch at discovery time,
+    /// or the epoch at evaluation time with the live council source.
+    pub council_epoch: Option<u64>,
+    /// How the council's ballots were counted, once the proxy evaluated the proposal.
+    pub evaluation: Option<EvaluationRecord>,
+    /// Title of the proposal as it was when the proxy discovered it.
+    pub title: Option<String>,
+    pub url: Option<String>,
 }
 
 #[derive(Clone)]
@@ -20,6 +33,11 @@ pub struct ProxyProposal {
     pub participation_status: ParticipationStatus,
     pub lock: bool,
     pub timer_scheduled_for: Option<u64>,
+    pub policy: Option<String>,
+    pub council_epoch: Option<u64>,
+    pub evaluation: Option<EvaluationRecord>,
+    pub title: Option<String>,
+    pub url: Option<String>,
 }
 
 impl From<ProxyProposalQuery> for ProxyProposal {
@@ -32,6 +50,11 @@ impl From<ProxyProposalQuery> for ProxyProposal {
             lock: false,
             timer_id: None,
             timer_scheduled_for: None,
+            policy: value.policy,
+            council_epoch: value.council_epoch,
+            evaluation: value.evaluation,
+            title: value.title,
+            url: value.url,
         }
     }
 }
@@ -44,14 +67,430 @@ impl From<ProxyProposal> for ProxyProposalQuery {
             creation_timestamp: value.creation_timestamp,
             participation_status: value.participation_status,
             timer_scheduled_for: value.timer_scheduled_for,
+            policy: value.policy,
+            council_epoch: value.council_epoch,
+            evaluation: value.evaluation,
+            title: value.title,
+            url: value.url,
         }
     }
 }
 
+/// How the council's ballots were counted when the proxy evaluated a proposal.
 #[derive(CandidType, Clone, Serialize, Deserialize)]
+pub struct EvaluationRecord {
+    /// Council members whose neuron was eligible to vote, the quorum denominator.
+    pub eligible_members: u64,
+    pub voted: u64,
+    pub yes: u64,
+    /// Council members whose neuron has no ballot on the proposal, e.g. because it was created
+    /// after the proposal or its dissolve delay was too short. They do not count toward the quorum.
+    pub ineligible_members: Vec<CouncilMember>,
+    /// Eligible members that voted no.
+    pub no: u64,
+    /// Eligible members that had not voted when the proposal was evaluated.
+    pub not_voted: u64,
+    /// The vote of each eligible member, after combining the ballots of their neurons. Empty in mirror mode.
+    pub member_ballots: Vec<MemberBallot>,
+    /// Suspended members that had not voted. They do not count toward the quorum.
+    pub suspended_members: Vec<CouncilMember>,
+    /// Members with a veto right on the proposal's action that voted no, making the proxy vote no.
+    pub vetoed_by: Vec<CouncilMember>,
+    /// In mirror mode, the neuron whose vote was copied. The counts are then those of the mirrored neurons.
+    pub mirrored_neuron: Option<String>,
+    /// In mirror mode, the mirrored neurons in their order at evaluation time: the lead, then the fallbacks.
+    /// Empty in council mode.
+    pub mirrored_neurons: Vec<String>,
+    /// Quorum of the voting policy that was applied. Not set in mirror mode, where no policy applies.
+    pub quorum_percent: Option<u8>,
+    /// Approval threshold of the voting policy that was applied. Not set in mirror mode.
+    pub approval_percent: Option<u8>,
+    /// When the proxy evaluated the proposal, in seconds since the epoch.
+    pub evaluated_at_seconds: u64,
+    /// The vote the proxy sent to governance. Not set if the proxy abstained.
+    pub vote_sent: Option<BallotChoice>,
+    /// How governance answered the vote. Not set if the proxy did not get to send it.
+    pub governance_response: Option<VoteResponse>,
+}
+
+/// A vote as cast by a council member or sent by the proxy.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub enum BallotChoice {
+    Yes,
+    No,
+    NotVoted,
+}
+
+/// A council member's vote on a proposal.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct MemberBallot {
+    pub name: String,
+    /// The member's primary neuron ID.
+    pub neuron_id: String,
+    pub vote: BallotChoice,
+    /// Primary neuron ID of the member whose vote was used because this member had not voted.
+    pub delegate: Option<String>,
+}
+
+/// How governance answered the proxy's vote.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum VoteResponse {
+    Registered,
+    /// Governance rejected the vote or could not be reached, with the error.
+    Failed(String),
+}
+
+/// How the proxy would vote on a watched proposal if it was evaluated now.
+#[derive(CandidType, Clone, Deserialize)]
+pub struct LiveTally {
+    pub proposal_id: u64,
+    /// Council epoch the proposal is evaluated against.
+    pub council_epoch: u64,
+    /// Name of the voting policy that applies to the proposal.
+    pub policy: String,
+    pub tally: EvaluationRecord,
+    /// The vote the proxy would send.
+    pub projected_vote: BallotChoice,
+    /// Eligible council members that have not voted yet.
+    pub pending_members: Vec<CouncilMember>,
+    /// Time left until the proxy evaluates the proposal, if its timer is scheduled.
+    pub seconds_until_evaluation: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct CouncilMember {
+    /// Unique within the council, compared case-insensitively.
     pub name: String,
+    /// Lowercase hex encoded ID of the member's primary neuron, which identifies the member.
+    /// Every neuron ID is unique within the council.
     pub neuron_id: String,
+    /// Further neurons the member votes with. Their ballots are combined with the primary neuron's
+    /// according to the neuron combine rule, so the member is counted once.
+    pub additional_neuron_ids: Option<Vec<String>>,
+    /// When the member joins the council, in seconds since the epoch. Members without a start join immediately.
+    pub term_start: Option<u64>,
+    /// When the member leaves the council, in seconds since the epoch. Members without an end serve until removed.
+    pub term_end: Option<u64>,
+    /// Principal the member can call the canister with to view and update their own record.
+    pub principal: Option<Principal>,
+}
+
+impl CouncilMember {
+    /// The primary neuron ID followed by the additional ones.
+    pub fn neuron_ids(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.neuron_id).chain(self.additional_neuron_ids.iter().flatten())
+    }
+
+    /// Returns true if the member's term covers `now`, in seconds since the epoch.
+    pub fn is_in_term(&self, now: u64) -> bool {
+        self.term_start.map_or(true, |start| start <= now)
+            && self.term_end.map_or(true, |end| now < end)
+    }
+}
+
+/// A council member letting another member vote on their behalf for a while. Members are identified by
+/// their primary neuron ID.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct Delegation {
+    pub delegator: String,
+    pub delegate: String,
+    /// When the delegation starts, in seconds since the epoch.
+    pub starts_at: u64,
+    /// When the delegation ends, in seconds since the epoch.
+    pub ends_at: u64,
+}
+
+/// A council member who can block proposals of some action types: when they vote no, the proxy votes no
+/// regardless of the rest of the council.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct VetoRight {
+    /// The member's primary neuron ID.
+    pub neuron_id: String,
+    pub action_types: Vec<u64>,
+}
+
+/// When council members that stopped voting are suspended.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct InactivityRule {
+    /// A member who left this many evaluated proposals in a row unvoted is suspended.
+    pub missed_proposals: u64,
+}
+
+/// A council member's recent participation in the proposals evaluated by the proxy.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct MemberActivity {
+    pub name: String,
+    /// The member's primary neuron ID.
+    pub neuron_id: String,
+    /// Evaluated proposals the member left unvoted since their last vote.
+    pub missed_in_a_row: u64,
+    /// When the member was suspended, in seconds since the epoch. Suspended members that have not voted
+    /// on a proposal do not count toward its quorum.
+    pub suspended_since: Option<u64>,
+}
+
+/// A suspension or reinstatement of a council member.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SuspensionEvent {
+    pub name: String,
+    pub neuron_id: String,
+    pub change: SuspensionChange,
+    pub at_seconds: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum SuspensionChange {
+    /// The member broke the inactivity rule.
+    Suspended { missed_proposals: u64 },
+    /// The member voted on an evaluated proposal.
+    ReinstatedByVote,
+    /// An admin reinstated the member.
+    ReinstatedBy(Principal),
+}
+
+/// How the ballots of a council member's neurons are combined into the member's vote.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum NeuronCombineRule {
+    /// The vote of the neuron that voted first.
+    #[default]
+    FirstVote,
+    /// The vote of the majority of the neurons that voted. A tie counts as a no.
+    MajorityOfNeurons,
+    /// Yes if any neuron voted yes, otherwise no if any neuron voted.
+    AnyYes,
+}
+
+/// Changes between two consecutive council epochs. Members are matched by neuron ID.
+#[derive(CandidType, Clone, Serialize, Deserialize, Default, Debug)]
+pub struct CouncilDiff {
+    /// The epoch the changes lead to.
+    pub epoch: u64,
+    pub added: Vec<CouncilMember>,
+    pub removed: Vec<CouncilMember>,
+    /// Members that kept their primary neuron but changed their name or additional neurons, as they are now.
+    pub updated: Vec<CouncilMember>,
+}
+
+impl CouncilDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Which council a proposal is evaluated against.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum CouncilSource {
+    /// The council as it was when the proposal was added to the watchlist.
+    #[default]
+    Snapshot,
+    /// The council as it is when the proposal is evaluated.
+    Live,
+}
+
+/// How the proxy decides its vote on a proposal.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum VotingMode {
+    /// The council's ballots are counted with the proposal's voting policy.
+    #[default]
+    Council,
+    /// The vote of the first listed neuron that voted is copied, e.g. a lead neuron followed by fallbacks.
+    /// The proxy abstains if none of them voted.
+    Mirror(Vec<String>),
+}
+
+/// A version of the council. A new epoch starts with every change to the council.
+#[derive(CandidType, Clone, Serialize, Deserialize)]
+pub struct CouncilEpoch {
+    pub epoch: u64,
+    pub started_at_seconds: u64,
+    pub members: Vec<CouncilMember>,
+    /// Changes from the previous epoch.
+    pub changes: CouncilDiff,
+}
+
+/// Roles that can be granted to principals. Each endpoint lists the roles allowed to call it.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub enum Role {
+    /// May call every endpoint. The canister's controllers are always admins.
+    Admin,
+    /// May handle routine operations: exclusion lists, flagged actions and filter rules.
+    Operator,
+    /// May read restricted data, such as the role assignments.
+    Auditor,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize)]
+pub struct RoleAssignment {
+    pub principal: Principal,
+    pub role: Role,
+}
+
+/// Principals that must jointly approve sensitive operations once the policy is set.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct MultisigPolicy {
+    pub signers: Vec<Principal>,
+    /// Number of signers that must approve an operation before it executes, including the one who submitted it.
+    pub threshold: u8,
+    /// How long an operation waits for approvals before it expires.
+    pub expiry_seconds: u64,
+}
+
+/// An operation that requires the approval of the multisig signers once a multisig policy is set.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum SensitiveAction {
+    EmergencyReset,
+    SetGovernanceId(Principal),
+    StopTimers,
+    CreateNeuron {
+        amount: Nat,
+        nonce: u64,
+    },
+    IncreaseDissolveDelay(u32),
+    /// Replaces the multisig policy, or removes it if not set.
+    SetMultisigPolicy(Option<MultisigPolicy>),
+}
+
+/// A sensitive operation waiting for the approval of the multisig signers.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct PendingAction {
+    pub id: u64,
+    pub action: SensitiveAction,
+    pub submitted_by: Principal,
+    pub submitted_at_seconds: u64,
+    pub expires_at_seconds: u64,
+    /// Signers that approved the operation, starting with the one who submitted it.
+    pub approvals: Vec<Principal>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub enum ApprovalOutcome {
+    /// The operation needs more approvals.
+    Pending { id: u64, approvals: u64 },
+    /// The threshold was met and the operation was executed.
+    Executed { id: u64 },
+}
+
+/// A security-relevant configuration change. Once a timelock delay is set, these changes
+/// are queued with `queue_change` and take effect after the delay.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum ConfigChange {
+    AddCouncilMember(CouncilMember),
+    UpdateCouncilMember {
+        neuron_id: String,
+        member: CouncilMember,
+    },
+    RemoveCouncilMember(String),
+    SetCouncil(Vec<CouncilMember>),
+    AllowActionType(u64),
+    DisallowActionType(u64),
+    SetExclusionMode(ExclusionMode),
+    SetVotingPolicy(VotingPolicy),
+    RemoveVotingPolicy(String),
+    SetTimelockDelay(u64),
+    SetVotingMode(VotingMode),
+    SetCouncilSource(CouncilSource),
+    SetNeuronCombineRule(NeuronCombineRule),
+    SetDelegation(Delegation),
+    RemoveDelegation(String),
+    SetVetoRight(VetoRight),
+    RemoveVetoRight(String),
+    SetInactivityRule(Option<InactivityRule>),
+    ReinstateCouncilMember(String),
+    AddFilterRule {
+        matcher: FilterMatcher,
+        action: FilterAction,
+    },
+    RemoveFilterRule(u64),
+    SetFlaggedActionHandling(FlaggedActionHandling),
+    AcknowledgeActionType(u64),
+}
+
+/// A configuration change waiting for its activation time.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct QueuedChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub queued_by: Principal,
+    pub queued_at_seconds: u64,
+    pub activates_at_seconds: u64,
+}
+
+/// The final outcome of a proposal handled by the proxy, as recorded in the decision log.
+#[derive(CandidType, Clone, Deserialize)]
+pub struct DecisionRecord {
+    pub proposal_id: u64,
+    pub action: u64,
+    /// Not set for proposals abstained on by a filter rule, which are never evaluated.
+    pub council_epoch: Option<u64>,
+    /// Not set if the proposal was not evaluated, e.g. because its voting period had already ended.
+    pub tally: Option<EvaluationRecord>,
+    /// The voting policy the tally was decided with. Not set if the proxy did not get to decide a vote.
+    pub policy: Option<VotingPolicy>,
+    /// The final participation status: `VotedFor` or `VotedAgainst` for votes registered by governance,
+    /// otherwise `Abstained`, `FailedToVote` or `TooLateToParticipate`.
+    pub vote: ParticipationStatus,
+    pub decided_at_seconds: u64,
+}
+
+/// How a council member took part in the votes decided by the proxy.
+#[derive(CandidType, Clone, Deserialize, Default)]
+pub struct MemberStats {
+    pub name: String,
+    /// The member's primary neuron ID.
+    pub neuron_id: String,
+    /// Decisions on proposals the member was eligible to vote on.
+    pub eligible: u64,
+    pub voted: u64,
+    pub yes: u64,
+    pub no: u64,
+    /// Votes of the member that matched the vote the proxy sent.
+    pub matched_proxy_vote: u64,
+    /// Decisions in which the member's delegate voted for the member.
+    pub delegated: u64,
+}
+
+/// An entry of the hash-chained decision log.
+#[derive(CandidType, Clone, Deserialize)]
+pub struct DecisionEntry {
+    /// Position in the decision log, starting at 0.
+    pub index: u64,
+    pub record: DecisionRecord,
+    /// Hash of the previous entry, or 32 zero bytes for the first entry.
+    pub previous_hash: Vec<u8>,
+    /// SHA-256 of `previous_hash` followed by the candid encoding of `record`.
+    pub hash: Vec<u8>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct DecisionLogPage {
+    pub entries: Vec<DecisionEntry>,
+    /// Number of entries in the whole decision log.
+    pub total: u64,
+    /// Hash of the last entry, which is the canister's certified data.
+    pub tip_hash: Vec<u8>,
+    /// Certificate of the canister's certified data, signed by the subnet.
+    pub certificate: Option<Vec<u8>>,
+}
+
+/// A call to a mutating endpoint.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AuditEntry {
+    /// Position in the audit log, starting at 0.
+    pub id: u64,
+    pub caller: Principal,
+    pub called_at_seconds: u64,
+    pub endpoint: String,
+    /// The call's arguments in Rust debug notation.
+    pub arguments: String,
+    /// The call's result in Rust debug notation.
+    pub result: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditEntry>,
+    /// Number of entries in the whole audit log.
+    pub total: u64,
 }
 
 #[derive(CandidType, Debug)]
@@ -64,6 +503,34 @@ pub enum CanisterError {
     WatchingIsAlreadyStopped,
     ProposalIsNotInWatchlist(u64),
     ProposalLocked(u64),
+    FilterRuleNotFound(u64),
+    VotingPolicyNotFound(String),
+    VotingPolicyInUse(String),
+    InvalidVotingPolicy(String),
+    UnknownActionType(u64),
+    ActionTypeAlreadyListed(u64),
+    InvalidNeuronId(String),
+    InvalidCouncilMemberName(String),
+    NeuronNotFound(String),
+    DuplicateCouncilMember(String),
+    CouncilMemberNotFound(String),
+    InvalidCouncilTerm(String),
+    InvalidCouncilMemberPrincipal(String),
+    RoleAssignmentNotFound,
+    ApprovalRequired,
+    InvalidMultisigPolicy(String),
+    PendingActionNotFound(u64),
+    AlreadyApproved(u64),
+    TimelockRequired,
+    QueuedChangeNotFound(u64),
+    InvalidEarlyDecisionInterval(u64),
+    InvalidInactivityRule(String),
+    MemberNotSuspended(String),
+    InvalidDelegation(String),
+    DelegationNotFound(String),
+    InvalidVetoRight(String),
+    VetoRightNotFound(String),
+    InvalidVotingMode(String),
 }
 
 #[derive(CandidType, Clone, Deserialize, Debug)]
@@ -73,4 +540,113 @@ pub enum ParticipationStatus {
     VotedFor,
     VotedAgainst,
     FailedToVote,
+    Abstained,
+    Skipped { reason: SkipReason },
+}
+
+/// Why the proposals scanner did not watch a proposal.
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub enum SkipReason {
+    /// The proposal's action is not permitted by the exclusion settings.
+    ExcludedAction,
+    /// A filter rule with the `Skip` action matched the proposal.
+    FilterRule { rule_id: u64 },
+    /// The proposal was no longer accepting votes when it was found.
+    AlreadyClosed,
+    /// The proxy's voting deadline, one hour before the proposal's, had passed when it was found.
+    DeadlinePassed,
+}
+
+/// How the action IDs configured with `allow_action_type` and `disallow_action_type` are interpreted.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum ExclusionMode {
+    /// Proposals are handled unless their action is in the exclusion list.
+    #[default]
+    Denylist,
+    /// Proposals are only handled if their action is in the allowed actions list.
+    Allowlist,
+}
+
+/// What the proxy does with generic functions registered after it started tracking the SNS's functions,
+/// until an operator allows, disallows or acknowledges them.
+#[derive(CandidType, Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum FlaggedActionHandling {
+    /// Flagged actions are handled like any other action under the current exclusion mode.
+    #[default]
+    FollowExclusionMode,
+    /// Proposals with a flagged action are ignored.
+    Ignore,
+}
+
+/// A nervous system function registered in the SNS, as last fetched from governance.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RegisteredFunction {
+    pub id: u64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_generic: bool,
+}
+
+/// An action ID together with the function it refers to.
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct ActionType {
+    pub id: u64,
+    /// Not set if no function with this ID is registered in the SNS (anymore).
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub is_generic: bool,
+    /// Set for generic functions registered after the proxy started tracking functions, until they are reviewed.
+    pub flagged: bool,
+}
+
+/// Part of a proposal that a filter rule is matched against.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum FilterMatcher {
+    /// Case-sensitive prefix of the title.
+    TitlePrefix(String),
+    /// Case-sensitive substring of the title.
+    TitleContains(String),
+    /// Case-insensitive keyword in the summary.
+    SummaryKeyword(String),
+    /// Domain of the proposal URL. Subdomains match as well.
+    UrlDomain(String),
+    /// Hex encoded ID of the neuron that submitted the proposal.
+    ProposerNeuron(String),
+}
+
+/// What the proxy does with a proposal matched by a filter rule.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum FilterAction {
+    /// The proposal is ignored.
+    Skip,
+    /// The proposal is recorded in the history, but the proxy does not vote on it.
+    ForceAbstain,
+    /// The proposal is evaluated with the voting policy of this name.
+    ApplyPolicy(String),
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct FilterRule {
+    pub id: u64,
+    pub matcher: FilterMatcher,
+    pub action: FilterAction,
+}
+
+/// Proposal fields used by `test_filter_rules` to find the rule that would apply.
+#[derive(CandidType, Clone, Deserialize)]
+pub struct FilterSample {
+    pub title: String,
+    pub summary: String,
+    pub url: String,
+    pub proposer: Option<String>,
+}
+
+/// Decides how the council's ballots are turned into the proxy's vote.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct VotingPolicy {
+    pub name: String,
+    /// The proxy only follows the council if more than this percentage of council members voted. It votes no otherwise.
+    pub quorum_percent: u8,
+    /// The proxy votes yes if more than this percentage of the voting council members voted yes.
+    pub approval_percent: u8,
 }
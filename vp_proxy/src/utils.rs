@@ -1,23 +1,29 @@
 use ic_exports::{
-    ic_cdk::{api::is_controller, call},
+    ic_cdk::call,
     ic_kit::{CallResult, Principal},
 };
 use ic_sns_governance::pb::v1::{
     manage_neuron::{self, RegisterVote},
-    ManageNeuron, ManageNeuronResponse, ProposalId,
+    ListNervousSystemFunctionsResponse, ManageNeuron, ManageNeuronResponse, NervousSystemFunction,
+    ProposalId,
 };
 
 use crate::{
-    state::{get_governance_canister_id, get_neuron},
-    types::CanisterError,
+    state::{get_governance_canister_id, get_neuron, get_roles},
+    types::{CanisterError, Role},
 };
 
-pub fn only_controller(caller: Principal) -> Result<(), CanisterError> {
-    if !is_controller(&caller) {
-        // only the controller (the DAO) should be able to call this function
-        return Err(CanisterError::Unauthorized);
+/// Allows the call if the caller holds one of `roles`. Admins, including the controllers, may call every endpoint.
+pub fn only_roles(caller: Principal, roles: &[Role]) -> Result<(), CanisterError> {
+    let caller_roles = get_roles(&caller);
+    if caller_roles.contains(&Role::Admin) || roles.iter().any(|role| caller_roles.contains(role)) {
+        return Ok(());
     }
-    Ok(())
+    Err(CanisterError::Unauthorized)
+}
+
+pub fn only_admin(caller: Principal) -> Result<(), CanisterError> {
+    only_roles(caller, &[])
 }
 
 pub fn not_anonymous(id: &Principal) -> Result<(), CanisterError> {
@@ -27,6 +33,11 @@ pub fn not_anonymous(id: &Principal) -> Result<(), CanisterError> {
     Ok(())
 }
 
+/// Encodes a neuron id the same way SNS governance keys proposal ballots.
+pub fn neuron_id_to_hex(id: &[u8]) -> String {
+    id.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 pub fn handle_intercanister_call<T>(
     canister_response: CallResult<(T,)>,
 ) -> Result<T, CanisterError> {
@@ -73,3 +84,16 @@ pub async fn vote(proposal_id: ProposalId, vote: i32) -> Result<(), CanisterErro
         "Could not handle the manage neuron response".to_string(),
     ))
 }
+
+/// Fetches the native and generic nervous system functions registered in the SNS.
+pub async fn list_nervous_system_functions() -> Result<Vec<NervousSystemFunction>, CanisterError> {
+    let governance_canister_id = get_governance_canister_id()?;
+
+    let list_functions_response =
+        call(governance_canister_id, "list_nervous_system_functions", ()).await;
+
+    let list_functions_handled =
+        handle_intercanister_call::<ListNervousSystemFunctionsResponse>(list_functions_response)?;
+
+    Ok(list_functions_handled.functions)
+}
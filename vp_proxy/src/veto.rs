@@ -0,0 +1,83 @@
+use crate::{
+    council::normalize_neuron_id,
+    state::{get_appointed_council_members, VETO_RIGHTS},
+    types::{CanisterError, VetoRight},
+};
+
+/// Validates a veto right and adds it, replacing the member's previous veto right.
+pub fn set_veto_right(right: VetoRight) -> Result<(), CanisterError> {
+    let neuron_id = normalize_neuron_id(&right.neuron_id)?;
+    if !get_appointed_council_members()
+        .iter()
+        .any(|member| member.neuron_id == neuron_id)
+    {
+        return Err(CanisterError::CouncilMemberNotFound(neuron_id));
+    }
+    let mut action_types = right.action_types;
+    action_types.sort();
+    action_types.dedup();
+    if action_types.is_empty() {
+        return Err(CanisterError::InvalidVetoRight(
+            "at least one action type is required".to_string(),
+        ));
+    }
+
+    VETO_RIGHTS.with(|rights| {
+        let mut rights = rights.borrow_mut();
+        rights.retain(|existing| existing.neuron_id != neuron_id);
+        rights.push(VetoRight {
+            neuron_id,
+            action_types,
+        });
+    });
+    Ok(())
+}
+
+/// Removes the veto right of the member with the given primary neuron.
+pub fn remove_veto_right(neuron_id: &str) -> Result<(), CanisterError> {
+    VETO_RIGHTS.with(|rights| {
+        let mut rights = rights.borrow_mut();
+        let count = rights.len();
+        rights.retain(|right| right.neuron_id != neuron_id);
+        if rights.len() == count {
+            return Err(CanisterError::VetoRightNotFound(neuron_id.to_string()));
+        }
+        Ok(())
+    })
+}
+
+/// Moves the veto right of the member whose primary neuron changed from `from` to `to`.
+pub fn rename_veto_right_holder(from: &str, to: &str) {
+    VETO_RIGHTS.with(|rights| {
+        for right in rights.borrow_mut().iter_mut() {
+            if right.neuron_id == from {
+                right.neuron_id = to.to_string();
+            }
+        }
+    });
+}
+
+/// Drops the veto rights of members that are no longer on the appointed council.
+pub fn remove_stale_veto_rights() {
+    let members = get_appointed_council_members();
+    VETO_RIGHTS.with(|rights| {
+        rights.borrow_mut().retain(|right| {
+            members
+                .iter()
+                .any(|member| member.neuron_id == right.neuron_id)
+        })
+    });
+}
+
+pub fn get_veto_rights() -> Vec<VetoRight> {
+    VETO_RIGHTS.with(|rights| rights.borrow().clone())
+}
+
+/// Returns the primary neuron IDs of the members who can veto proposals with the given action.
+pub fn get_veto_holders(action: u64) -> Vec<String> {
+    get_veto_rights()
+        .into_iter()
+        .filter(|right| right.action_types.contains(&action))
+        .map(|right| right.neuron_id)
+        .collect()
+}
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+
+use ic_exports::ic_cdk::api::time;
+use ic_sns_governance::pb::v1::{Ballot, Vote};
+
+use crate::{
+    state::{get_filter_rules, get_voting_policy, DEFAULT_VOTING_POLICY, VOTING_POLICIES},
+    types::{
+        BallotChoice, CanisterError, CouncilMember, Delegation, EvaluationRecord, FilterAction,
+        MemberBallot, NeuronCombineRule, VotingPolicy,
+    },
+};
+
+/// How the council voted on a proposal.
+pub struct CouncilTally {
+    /// Members whose neuron was eligible to vote on the proposal.
+    pub members: u64,
+    pub voted: u64,
+    pub yes: u64,
+    /// Members whose neuron has no ballot on the proposal.
+    pub ineligible: Vec<CouncilMember>,
+    /// The combined vote of each eligible member.
+    pub ballots: Vec<MemberBallot>,
+    /// Members whose vote may still change: members that voted with some of their neurons only, and
+    /// members represented by their delegate.
+    pub provisional: u64,
+    /// Suspended members that have not voted. They do not count toward the quorum.
+    pub suspended: Vec<CouncilMember>,
+    /// Members with a veto right that voted no themselves.
+    pub vetoed_by: Vec<CouncilMember>,
+    /// Members of `vetoed_by` whose no may still change.
+    pub provisional_vetoes: u64,
+    /// Members with a veto right that may still vote no themselves.
+    pub pending_vetoes: u64,
+}
+
+/// Counts the ballots of the council members, each member once. Neurons that have not voted yet have an
+/// unspecified ballot, while neurons that were not eligible to vote, e.g. because they were created after
+/// the proposal, have none. A member is eligible if any of their neurons is. A member who has not voted
+/// takes the vote of their delegate in `delegations`, if any. Members whose primary neuron is in `suspended`
+/// are only counted if they voted or their delegate did. Members whose primary neuron is in `veto_holders`
+/// veto the proposal by voting no themselves; a delegated no is not a veto.
+pub fn tally_council(
+    members: &[CouncilMember],
+    ballots: &BTreeMap<String, Ballot>,
+    rule: NeuronCombineRule,
+    suspended: &[String],
+    delegations: &[Delegation],
+    veto_holders: &[String],
+) -> CouncilTally {
+    let mut tally = CouncilTally {
+        members: 0,
+        voted: 0,
+        yes: 0,
+        ineligible: vec![],
+        ballots: vec![],
+        provisional: 0,
+        suspended: vec![],
+        vetoed_by: vec![],
+        provisional_vetoes: 0,
+        pending_vetoes: 0,
+    };
+    let votes: Vec<Option<(BallotChoice, bool)>> = members
+        .iter()
+        .map(|member| member_vote(member, ballots, rule))
+        .collect();
+    // the vote of the member's delegate, with the delegate's primary neuron ID
+    let delegated_vote = |member: &CouncilMember| -> Option<(BallotChoice, String)> {
+        let delegation = delegations
+            .iter()
+            .find(|delegation| delegation.delegator == member.neuron_id)?;
+        let position = members
+            .iter()
+            .position(|delegate| delegate.neuron_id == delegation.delegate)?;
+        let (vote, _) = votes[position]?;
+        (vote != BallotChoice::NotVoted).then(|| (vote, delegation.delegate.clone()))
+    };
+
+    for (member, own_vote) in members.iter().zip(&votes) {
+        let Some((mut vote, mut provisional)) = *own_vote else {
+            tally.ineligible.push(member.clone());
+            continue;
+        };
+        let mut delegate = None;
+        if vote == BallotChoice::NotVoted {
+            if let Some((delegate_vote, delegate_neuron_id)) = delegated_vote(member) {
+                // the member's own vote replaces the delegate's once cast
+                vote = delegate_vote;
+                provisional = true;
+                delegate = Some(delegate_neuron_id);
+            }
+        }
+        if veto_holders.contains(&member.neuron_id) {
+            if vote == BallotChoice::No && delegate.is_none() {
+                tally.vetoed_by.push(member.clone());
+                if provisional {
+                    tally.provisional_vetoes += 1;
+                }
+            } else if vote == BallotChoice::NotVoted || provisional {
+                tally.pending_vetoes += 1;
+            }
+        }
+        if vote == BallotChoice::NotVoted && suspended.contains(&member.neuron_id) {
+            tally.suspended.push(member.clone());
+            continue;
+        }
+
+        tally.members += 1;
+        match vote {
+            BallotChoice::Yes => {
+                tally.voted += 1;
+                tally.yes += 1;
+            }
+            BallotChoice::No => tally.voted += 1,
+            BallotChoice::NotVoted => {}
+        }
+        if provisional {
+            tally.provisional += 1;
+        }
+        tally.ballots.push(MemberBallot {
+            name: member.name.clone(),
+            neuron_id: member.neuron_id.clone(),
+            vote,
+            delegate,
+        });
+    }
+
+    tally
+}
+
+/// Returns the member's vote, combined from the ballots of their neurons, and whether it may still change
+/// because some of their neurons have not voted. Returns `None` if none of the neurons is eligible.
+fn member_vote(
+    member: &CouncilMember,
+    ballots: &BTreeMap<String, Ballot>,
+    rule: NeuronCombineRule,
+) -> Option<(BallotChoice, bool)> {
+    let member_ballots: Vec<&Ballot> = member
+        .neuron_ids()
+        .filter_map(|neuron_id| ballots.get(neuron_id))
+        .collect();
+    if member_ballots.is_empty() {
+        return None;
+    }
+    let vote = match combine_ballots(&member_ballots, rule) {
+        Vote::Yes => BallotChoice::Yes,
+        Vote::No => BallotChoice::No,
+        Vote::Unspecified => BallotChoice::NotVoted,
+    };
+    let provisional = vote != BallotChoice::NotVoted
+        && rule != NeuronCombineRule::FirstVote
+        && member_ballots
+            .iter()
+            .any(|ballot| ballot.vote == Vote::Unspecified as i32);
+    Some((vote, provisional))
+}
+
+/// Combines the ballots of a member's neurons into the member's vote.
+fn combine_ballots(ballots: &[&Ballot], rule: NeuronCombineRule) -> Vote {
+    let cast: Vec<&&Ballot> = ballots
+        .iter()
+        .filter(|ballot| ballot.vote == Vote::Yes as i32 || ballot.vote == Vote::No as i32)
+        .collect();
+    if cast.is_empty() {
+        return Vote::Unspecified;
+    }
+    let yes = cast
+        .iter()
+        .filter(|ballot| ballot.vote == Vote::Yes as i32)
+        .count();
+
+    match rule {
+        NeuronCombineRule::FirstVote => {
+            let first = cast
+                .iter()
+                .min_by_key(|ballot| ballot.cast_timestamp_seconds)
+                .unwrap();
+            if first.vote == Vote::Yes as i32 {
+                Vote::Yes
+            } else {
+                Vote::No
+            }
+        }
+        NeuronCombineRule::MajorityOfNeurons if yes * 2 > cast.len() => Vote::Yes,
+        NeuronCombineRule::AnyYes if yes > 0 => Vote::Yes,
+        _ => Vote::No,
+    }
+}
+
+impl From<CouncilTally> for EvaluationRecord {
+    fn from(tally: CouncilTally) -> Self {
+        Self {
+            eligible_members: tally.members,
+            voted: tally.voted,
+            yes: tally.yes,
+            ineligible_members: tally.ineligible,
+            no: tally.voted - tally.yes,
+            not_voted: tally.members - tally.voted,
+            member_ballots: tally.ballots,
+            suspended_members: tally.suspended,
+            vetoed_by: tally.vetoed_by,
+            mirrored_neuron: None,
+            mirrored_neurons: vec![],
+            quorum_percent: None,
+            approval_percent: None,
+            evaluated_at_seconds: time() / 1_000_000_000,
+            vote_sent: None,
+            governance_response: None,
+        }
+    }
+}
+
+impl VotingPolicy {
+    /// Returns the vote the proxy casts for the given council tally: no if vetoed, otherwise as the counts
+    /// meet the policy.
+    pub fn decide(&self, tally: &CouncilTally) -> Vote {
+        if !tally.vetoed_by.is_empty() {
+            return Vote::No;
+        }
+        self.decide_counts(tally.members, tally.voted, tally.yes)
+    }
+
+    /// Returns true if the vote decided for `tally` stays the same however the council votes from now on.
+    /// Members that have not voted may still vote either way, and provisional votes may still flip.
+    /// Suspended members rejoin the quorum if they vote or are reinstated by an admin, and with
+    /// `suspensions_possible`, members that have not voted may be suspended by the evaluation of another
+    /// proposal, leaving the quorum. A veto that can no longer change is final, while a yes is not as long
+    /// as a veto may still come.
+    pub fn is_final(&self, tally: &CouncilTally, suspensions_possible: bool) -> bool {
+        if tally.vetoed_by.len() as u64 > tally.provisional_vetoes {
+            return true;
+        }
+        let decision = self.decide(tally);
+        if decision == Vote::Yes && tally.pending_vetoes > 0 {
+            return false;
+        }
+        let min_yes = tally.yes.saturating_sub(tally.provisional);
+        let suspended = tally.suspended.len() as u64;
+        let unvoted = tally.members - tally.voted;
+        let max_leaving = if suspensions_possible { unvoted } else { 0 };
+        (0..=suspended).all(|returning| {
+            (0..=max_leaving).all(|leaving| {
+                let members = tally.members + returning - leaving;
+                (0..=members - tally.voted).all(|new_votes| {
+                    let voted = tally.voted + new_votes;
+                    let max_yes = (tally.yes + tally.provisional + new_votes).min(voted);
+                    (min_yes..=max_yes)
+                        .all(|yes| self.decide_counts(members, voted, yes) == decision)
+                })
+            })
+        })
+    }
+
+    fn decide_counts(&self, members: u64, voted: u64, yes: u64) -> Vote {
+        let quorum_reached = voted * 100 > members * self.quorum_percent as u64;
+        let approved = yes * 100 > voted * self.approval_percent as u64;
+
+        if quorum_reached && approved {
+            Vote::Yes
+        } else {
+            Vote::No
+        }
+    }
+}
+
+pub fn validate_voting_policy(policy: &VotingPolicy) -> Result<(), CanisterError> {
+    if policy.name.is_empty() {
+        return Err(CanisterError::InvalidVotingPolicy(
+            "Policy name cannot be empty".to_string(),
+        ));
+    }
+    if policy.quorum_percent > 100 || policy.approval_percent > 100 {
+        return Err(CanisterError::InvalidVotingPolicy(
+            "Percentages cannot exceed 100".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Adds the validated policy, or replaces the policy with the same name.
+pub fn set_voting_policy(policy: VotingPolicy) {
+    VOTING_POLICIES.with(|policies| {
+        let mut policies = policies.borrow_mut();
+        match policies
+            .iter_mut()
+            .find(|existing| existing.name == policy.name)
+        {
+            Some(existing) => *existing = policy,
+            None => policies.push(policy),
+        }
+    });
+}
+
+/// Removes a policy unless it is the default policy or a filter rule applies it.
+pub fn remove_voting_policy(name: &str) -> Result<(), CanisterError> {
+    get_voting_policy(Some(name))?;
+    let in_use = get_filter_rules().iter().any(|rule| match &rule.action {
+        FilterAction::ApplyPolicy(policy) => policy == name,
+        _ => false,
+    });
+    if name == DEFAULT_VOTING_POLICY || in_use {
+        return Err(CanisterError::VotingPolicyInUse(name.to_string()));
+    }
+    VOTING_POLICIES.with(|policies| policies.borrow_mut().retain(|policy| policy.name != name));
+    Ok(())
+}